@@ -123,7 +123,7 @@ echo "  ✅ Code formatting (rustfmt)"
 echo "  ✅ Linting (clippy)"
 echo "  ✅ Unit tests (cargo test)"
 echo "  ✅ Release build"
-echo "  ✅ Integration tests (14 end-to-end tests)"
+echo "  ✅ Integration tests (end-to-end)"
 echo "  ✅ Code quality checks"
 echo ""
 echo "Your git-pair is ready for production! 🚀"
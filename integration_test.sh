@@ -15,6 +15,11 @@ cd "$TEST_DIR"
 
 echo "📁 Created test directory: $TEST_DIR"
 
+# Keep the repository registry inside the test directory
+export GIT_PAIR_REPOS_FILE="$TEST_DIR/test-repos"
+export GIT_PAIR_RECENT_FILE="$TEST_DIR/test-recent"
+export GIT_PAIR_PRESETS_FILE="$TEST_DIR/test-presets"
+
 # Initialize a git repo
 git init
 git config user.name "Test User"
@@ -27,9 +32,10 @@ echo "✅ Test 1: Initialize git-pair"
 ./git-pair init
 # Get the current branch name and check for branch-specific config file
 CURRENT_BRANCH=$(git branch --show-current)
-if [ ! -f ".git/git-pair/config-$CURRENT_BRANCH" ]; then
+CONFIG_NAME=$(awk -F '\t' -v branch="$CURRENT_BRANCH" '$2 == branch { print $1 }' .git/git-pair/branches)
+if [ -z "$CONFIG_NAME" ] || [ ! -f ".git/git-pair/$CONFIG_NAME" ]; then
     echo "❌ Branch-specific config file not created"
-    echo "Expected: .git/git-pair/config-$CURRENT_BRANCH"
+    echo "Expected an entry for '$CURRENT_BRANCH' in .git/git-pair/branches"
     ls -la .git/git-pair/ || echo "git-pair directory doesn't exist"
     exit 1
 fi
@@ -53,16 +59,16 @@ if [ ! -f ".git/hooks/prepare-commit-msg" ]; then
 fi
 
 HOOK_CONTENT=$(cat .git/hooks/prepare-commit-msg)
-# With per-branch config, the hook reads co-authors dynamically from config files
+# With per-branch config, the hook reads co-authors dynamically from the trailer block files
 # Check that the hook contains the dynamic logic instead of hard-coded names
-if [[ ! "$HOOK_CONTENT" == *"CURRENT_BRANCH"* ]] || [[ ! "$HOOK_CONTENT" == *"CONFIG_FILE"* ]]; then
+if [[ ! "$HOOK_CONTENT" == *"CURRENT_BRANCH"* ]] || [[ ! "$HOOK_CONTENT" == *"TRAILERS_FILE"* ]]; then
     echo "❌ Git hook doesn't contain per-branch logic"
     echo "Hook: $HOOK_CONTENT"
     exit 1
 fi
 
 # Check that the branch-specific config file contains the co-authors
-CONFIG_CONTENT=$(cat ".git/git-pair/config-$CURRENT_BRANCH")
+CONFIG_CONTENT=$(cat ".git/git-pair/$CONFIG_NAME")
 if [[ ! "$CONFIG_CONTENT" == *"John Doe"* ]] || [[ ! "$CONFIG_CONTENT" == *"Jane Smith"* ]]; then
     echo "❌ Branch config doesn't contain co-authors"
     echo "Config: $CONFIG_CONTENT"
@@ -92,19 +98,21 @@ fi
 
 echo "✅ Test 8: Error handling - not initialized"
 rm -rf .git/git-pair
-ERROR_OUTPUT=$(./git-pair add Test User test@example.com 2>&1 || true)
-if [[ ! "$ERROR_OUTPUT" == *"not initialized for branch"* ]]; then
+EXIT_CODE=0
+ERROR_OUTPUT=$(./git-pair add Test User test@example.com 2>&1) || EXIT_CODE=$?
+if [[ ! "$ERROR_OUTPUT" == *"not initialized for branch"* ]] || [ "$EXIT_CODE" -ne 4 ]; then
     echo "❌ Error handling for uninitialized state failed"
-    echo "Error output: $ERROR_OUTPUT"
+    echo "Error output: $ERROR_OUTPUT (exit code $EXIT_CODE)"
     exit 1
 fi
 
 echo "✅ Test 9: Error handling - not in git repo"
 cd /tmp
-ERROR_OUTPUT=$("$TEST_DIR/git-pair" init 2>&1 || true)
-if [[ ! "$ERROR_OUTPUT" == *"Not in a git repository"* ]]; then
+EXIT_CODE=0
+ERROR_OUTPUT=$("$TEST_DIR/git-pair" init 2>&1) || EXIT_CODE=$?
+if [[ ! "$ERROR_OUTPUT" == *"Not in a git repository"* ]] || [ "$EXIT_CODE" -ne 3 ]; then
     echo "❌ Error handling for non-git directory failed"
-    echo "Error output: $ERROR_OUTPUT"
+    echo "Error output: $ERROR_OUTPUT (exit code $EXIT_CODE)"
     exit 1
 fi
 
@@ -149,10 +157,11 @@ if [[ ! "$QUICK_STATUS" == *"Alice Johnson"* ]] || [[ ! "$QUICK_STATUS" == *"Bob
 fi
 
 echo "✅ Test 14: Global roster - non-existent alias"
-ERROR_OUTPUT=$(./git-pair add charlie 2>&1 || true)
-if [[ ! "$ERROR_OUTPUT" == *"not found in global roster"* ]]; then
+EXIT_CODE=0
+ERROR_OUTPUT=$(./git-pair add charlie 2>&1) || EXIT_CODE=$?
+if [[ ! "$ERROR_OUTPUT" == *"not found in the roster"* ]] || [ "$EXIT_CODE" -ne 6 ]; then
     echo "❌ Non-existent alias error handling failed"
-    echo "Error output: $ERROR_OUTPUT"
+    echo "Error output: $ERROR_OUTPUT (exit code $EXIT_CODE)"
     exit 1
 fi
 
@@ -188,10 +197,11 @@ if [[ "$REMOVE_STATUS3" == *"Alice Johnson"* ]] || [[ ! "$REMOVE_STATUS3" == *"B
 fi
 
 echo "✅ Test 18: Remove non-existent co-author"
-ERROR_OUTPUT2=$(./git-pair remove "Charlie Brown" 2>&1 || true)
-if [[ ! "$ERROR_OUTPUT2" == *"not found on branch"* ]]; then
+EXIT_CODE=0
+ERROR_OUTPUT2=$(./git-pair remove "Charlie Brown" 2>&1) || EXIT_CODE=$?
+if [[ ! "$ERROR_OUTPUT2" == *"not found on branch"* ]] || [ "$EXIT_CODE" -ne 8 ]; then
     echo "❌ Non-existent co-author remove error handling failed"
-    echo "Error output: $ERROR_OUTPUT2"
+    echo "Error output: $ERROR_OUTPUT2 (exit code $EXIT_CODE)"
     exit 1
 fi
 
@@ -208,13 +218,202 @@ if [[ ! "$FINAL_STATUS" == *"No co-authors configured"* ]]; then
     exit 1
 fi
 
+echo "✅ Test 20: Repository registry"
+if ! grep -q "$(cd "$TEST_DIR" && pwd -P)" "$GIT_PAIR_REPOS_FILE"; then
+    echo "❌ init did not record the repository in the registry"
+    cat "$GIT_PAIR_REPOS_FILE" || echo "registry file doesn't exist"
+    exit 1
+fi
+
+echo "✅ Test 21: Binary hook engine"
+./git-pair config hook-engine binary
+./git-pair add Carol Binary carol@example.com
+if ! grep -q "git-pair hook run prepare-commit-msg" .git/hooks/prepare-commit-msg; then
+    echo "❌ Binary hook engine did not install the shim hook"
+    cat .git/hooks/prepare-commit-msg
+    exit 1
+fi
+echo "shim test" > shim.txt
+git add shim.txt
+PATH="$PWD:$PATH" git commit -m "Commit through the shim hook"
+if ! git log -1 --pretty=format:"%B" | grep -q "Co-authored-by: Carol Binary <carol@example.com>"; then
+    echo "❌ Shim hook did not add the co-author"
+    git log -1 --pretty=format:"%B"
+    exit 1
+fi
+./git-pair clear
+./git-pair config --unset hook-engine
+
+echo "✅ Test 22: Error codes in JSON"
+EXIT_CODE=0
+ERROR_OUTPUT=$(./git-pair --json add charlie 2>&1) || EXIT_CODE=$?
+if [[ ! "$ERROR_OUTPUT" == *'"code":"ALIAS_NOT_FOUND"'* ]] || [ "$EXIT_CODE" -ne 6 ]; then
+    echo "❌ JSON error for a missing alias failed"
+    echo "Error output: $ERROR_OUTPUT (exit code $EXIT_CODE)"
+    exit 1
+fi
+EXIT_CODE=0
+./git-pair --json remove 2>/dev/null || EXIT_CODE=$?
+if [ "$EXIT_CODE" -ne 2 ]; then
+    echo "❌ Usage error did not exit with 2 (exit code $EXIT_CODE)"
+    exit 1
+fi
+EXIT_CODE=0
+ERROR_OUTPUT=$(./git-pair --json remove nobody@example.com 2>&1) || EXIT_CODE=$?
+if [[ ! "$ERROR_OUTPUT" == *'"code":"COAUTHOR_NOT_FOUND"'* ]] || [ "$EXIT_CODE" -ne 8 ]; then
+    echo "❌ JSON error for a missing co-author failed"
+    echo "Error output: $ERROR_OUTPUT (exit code $EXIT_CODE)"
+    exit 1
+fi
+NOT_A_REPO=$(mktemp -d)
+EXIT_CODE=0
+ERROR_OUTPUT=$(./git-pair --json -C "$NOT_A_REPO" status 2>&1) || EXIT_CODE=$?
+rm -rf "$NOT_A_REPO"
+if [[ ! "$ERROR_OUTPUT" == *'"code":"NOT_A_REPO"'* ]] || [ "$EXIT_CODE" -ne 3 ]; then
+    echo "❌ JSON error outside a repository failed"
+    echo "Error output: $ERROR_OUTPUT (exit code $EXIT_CODE)"
+    exit 1
+fi
+
+echo "✅ Test 23: Dry runs write nothing"
+./git-pair add John Doe john.doe@company.com
+# An outdated hook and a config from before hashed names, both rewritten by a real run
+sed -i.bak 's/^# BEGIN git-pair v.*/# BEGIN git-pair v0.0.1/' .git/hooks/prepare-commit-msg
+rm -f .git/hooks/prepare-commit-msg.bak
+CONFIG_NAME=$(awk -F '\t' -v branch="$CURRENT_BRANCH" '$2 == branch { print $1 }' .git/git-pair/branches)
+mv ".git/git-pair/$CONFIG_NAME" ".git/git-pair/config-$CURRENT_BRANCH"
+rm -f .git/git-pair/branches .git/git-pair/lock-*
+snapshot() {
+    find .git/git-pair .git/hooks -type f | sort | while read -r file; do
+        echo "$file $(cksum < "$file")"
+    done
+}
+BEFORE=$(snapshot)
+DRY_RUN_OUTPUT=$(./git-pair add Jane Smith jane.smith@company.com --dry-run)
+./git-pair remove John --dry-run > /dev/null
+./git-pair clear --dry-run > /dev/null
+./git-pair check-config --fix --dry-run > /dev/null
+AFTER=$(snapshot)
+if [ "$BEFORE" != "$AFTER" ]; then
+    echo "❌ A dry run changed files"
+    diff <(echo "$BEFORE") <(echo "$AFTER") || true
+    exit 1
+fi
+if [[ ! "$DRY_RUN_OUTPUT" == *"Would add co-author: Jane Smith"* ]]; then
+    echo "❌ Dry run output doesn't say what would be added"
+    echo "Output: $DRY_RUN_OUTPUT"
+    exit 1
+fi
+./git-pair clear
+
+echo "✅ Test 24: Status only suggests a repair for a skipped hook"
+./git-pair add John Doe john.doe@company.com
+./git-pair snooze 1h > /dev/null
+git commit -q --allow-empty -m "Snoozed commit"
+./git-pair resume > /dev/null
+STATUS_OUTPUT=$(./git-pair status)
+if [[ ! "$STATUS_OUTPUT" == *"Last commit included co-authors: no"* ]] || [[ "$STATUS_OUTPUT" == *"repair-hook"* ]]; then
+    echo "❌ Status suggested a repair after a snoozed commit"
+    echo "Output: $STATUS_OUTPUT"
+    exit 1
+fi
+git -c core.hooksPath=/dev/null commit -q --allow-empty -m "Commit without hooks"
+STATUS_OUTPUT=$(./git-pair status)
+if [[ ! "$STATUS_OUTPUT" == *"run 'git-pair check-config' or 'git-pair repair-hook'"* ]]; then
+    echo "❌ Status didn't suggest a repair after a skipped hook"
+    echo "Output: $STATUS_OUTPUT"
+    exit 1
+fi
+./git-pair clear
+
+echo "✅ Test 25: Hook commands never fail a commit, even after global flags"
+NOT_A_REPO=$(mktemp -d)
+echo "Message" > "$NOT_A_REPO/COMMIT_EDITMSG"
+EXIT_CODE=0
+./git-pair -C "$NOT_A_REPO" hook run prepare-commit-msg "$NOT_A_REPO/COMMIT_EDITMSG" 2> /dev/null || EXIT_CODE=$?
+if [ "$EXIT_CODE" -ne 0 ]; then
+    echo "❌ A failing hook run after -C exited with $EXIT_CODE"
+    rm -rf "$NOT_A_REPO"
+    exit 1
+fi
+EXIT_CODE=0
+./git-pair -C "$NOT_A_REPO" hook test 2> /dev/null || EXIT_CODE=$?
+rm -rf "$NOT_A_REPO"
+if [ "$EXIT_CODE" -ne 3 ]; then
+    echo "❌ hook test outside a repository exited with $EXIT_CODE instead of 3"
+    exit 1
+fi
+
+echo "✅ Test 26: -C and --repo run commands on another repository"
+./git-pair add John Doe john.doe@company.com
+OTHER_DIR=$(mktemp -d)
+REPO_NAME=$(basename "$TEST_DIR")
+STATUS_OUTPUT=$(cd "$OTHER_DIR" && "$TEST_DIR/git-pair" -C "$TEST_DIR" status)
+if [[ ! "$STATUS_OUTPUT" == *"John Doe <john.doe@company.com>"* ]]; then
+    echo "❌ -C didn't run status in the other repository"
+    echo "Output: $STATUS_OUTPUT"
+    exit 1
+fi
+# Several -C resolve one after the other, like git's
+(cd "$OTHER_DIR" && "$TEST_DIR/git-pair" --repo "$(dirname "$TEST_DIR")" -C "$REPO_NAME" remove john.doe@company.com > /dev/null)
+if [[ "$(./git-pair status)" == *"John Doe"* ]]; then
+    echo "❌ --repo followed by a relative -C didn't remove the co-author"
+    exit 1
+fi
+EXIT_CODE=0
+ERROR_OUTPUT=$(./git-pair -C "$OTHER_DIR/missing" status 2>&1) || EXIT_CODE=$?
+rm -rf "$OTHER_DIR"
+if [[ ! "$ERROR_OUTPUT" == *"changing to '$OTHER_DIR/missing'"* ]] || [ "$EXIT_CODE" -eq 0 ]; then
+    echo "❌ -C with a missing directory didn't fail"
+    echo "Error output: $ERROR_OUTPUT (exit code $EXIT_CODE)"
+    exit 1
+fi
+EXIT_CODE=0
+./git-pair -C > /dev/null 2>&1 || EXIT_CODE=$?
+if [ "$EXIT_CODE" -ne 2 ]; then
+    echo "❌ -C without a path exited with $EXIT_CODE instead of the usage status 2"
+    exit 1
+fi
+
+echo "✅ Test 27: solo pauses pairing and keeps the co-authors"
+./git-pair add John Doe john.doe@company.com
+SOLO_OUTPUT=$(./git-pair solo)
+STATUS_OUTPUT=$(./git-pair status)
+if [[ ! "$STATUS_OUTPUT" == *"Paused:"* ]] || [[ ! "$STATUS_OUTPUT" == *"John Doe"* ]]; then
+    echo "❌ solo didn't pause the branch and keep its co-authors"
+    echo "Output: $SOLO_OUTPUT / $STATUS_OUTPUT"
+    exit 1
+fi
+git commit -q --allow-empty -m "Solo commit"
+if git log -1 --format=%B | grep -q "Co-authored-by:"; then
+    echo "❌ A commit made after solo has co-authors"
+    exit 1
+fi
+./git-pair resume > /dev/null
+git commit -q --allow-empty -m "Paired again"
+if ! git log -1 --format=%B | grep -q "Co-authored-by: John Doe <john.doe@company.com>"; then
+    echo "❌ resume after solo didn't bring the co-authors back"
+    exit 1
+fi
+EXIT_CODE=0
+ERROR_OUTPUT=$(./git-pair add --global solo "Solo Person" solo@example.com 2>&1) || EXIT_CODE=$?
+if [[ ! "$ERROR_OUTPUT" == *"is reserved because it is a git-pair command name"* ]] || [ "$EXIT_CODE" -ne 10 ]; then
+    echo "❌ solo was accepted as an alias although it's a command"
+    echo "Error output: $ERROR_OUTPUT (exit code $EXIT_CODE)"
+    exit 1
+fi
+./git-pair clear
+
 # Cleanup temporary HOME
 rm -rf "$TEMP_ROSTER_FILE"
 unset GIT_PAIR_ROSTER_FILE
+unset GIT_PAIR_REPOS_FILE
+unset GIT_PAIR_RECENT_FILE
+unset GIT_PAIR_PRESETS_FILE
 
 # Cleanup
 cd "$OLDPWD"
 rm -rf "$TEST_DIR"
 
-echo "🎉 All 19 integration tests passed!"
+echo "🎉 All 27 integration tests passed!"
 echo "🚀 git-pair with remove functionality is ready for use!"
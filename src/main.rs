@@ -1,95 +1,1786 @@
 use git_pair::{
-    add_coauthor, add_coauthor_from_global, add_global_coauthor, clear_coauthors, get_coauthors,
-    get_global_roster, init_pair_config, remove_coauthor,
+    add_coauthor_from_global_with, add_coauthor_from_provider, add_coauthor_with,
+    add_global_coauthor_with, add_group, add_repo_coauthor_with, adopt_coauthors, alias_info,
+    amend_commits, announce, apply_branch_prune, apply_group, apply_roster_dedupe, apply_snippet,
+    archive_global_aliases, archive_unused_aliases, audit_recent_commits, auth_login, auth_logout,
+    auth_status, backup_global_roster, blame, check_config, check_hook, check_pairing, clean_hooks,
+    clear_cache, clear_coauthors_with, clear_default_coauthors, commit_message_trailers,
+    commit_with, common_alias_prefix, compare_pairing_stats, complete_alias, complete_email,
+    completion_script, decrypt_global_roster, diff_branch_coauthors, diff_lines, doctor,
+    edit_branch_config, enable_enforcement, encrypt_global_roster, enforce_branches,
+    environment_report, events_end, expire_coauthors, get_archived_aliases, get_coauthors,
+    get_driver, get_expiring_coauthors, get_footer, get_global_roster, get_groups,
+    get_max_coauthors, get_once_coauthors, get_other_trailers, get_pairing_stats,
+    get_pairing_streaks, get_pairing_time, get_pins, get_pr_body, get_presets, get_recent_pairings,
+    get_repo_authors, get_repo_roster, get_roster, get_setting, get_settings, get_standup,
+    git_identity, has_placeholder_email, import_github_org, import_gitlab_group, import_history,
+    import_repo_authors, import_roster_csv, init_from_default, init_pair_config_with,
+    install_hooks, install_mode, is_disabled_by_env, is_enforcing, is_hook_free, is_paused,
+    json_schema, json_string, last_commit_has_coauthors, last_commit_missed_coauthors,
+    lint_global_roster, list_branch_pairings, list_repo_pairings, lookup_provider_user, mob_done,
+    mob_next, mob_start_with, mob_status, move_branch_config, pairing_graph, pairing_heatmap,
+    parse_duration, parse_roster_selection, parse_stats_period, pause_pairing, period_windows,
+    pin_alias, plan_branch_prune, plan_roster_dedupe, planned_fixes, preview_commit_message,
+    prompt_segment_with, pull_roster, push_roster, read_events, read_snippet_source,
+    remove_coauthor_with, remove_global_alias, remove_group, rename_global_alias, render_qr_code,
+    renew_coauthors, repair_hook, repo_roster_file, reset_driver, restore_global_roster,
+    resume_pairing, roster_backups, roster_conflicts, roster_json_with, roster_porcelain_with,
+    rotate, rotate_driver, run_commit_msg_hook, run_hook_impl, run_once, run_post_checkout_hook,
+    run_post_commit_hook, run_prepare_commit_msg_hook, save_preset, set_default_coauthors,
+    set_driver, set_enforcement, set_footer, set_git_identity, set_setting, setup_git_aliases,
+    share_snippet, simulate_hook, snooze, snoozed_until, squash_message_coauthors, stats_csv,
+    stats_json, status_json, status_porcelain, sync_github_teams, sync_gitlab_teams, sync_roster,
+    team_aliases, unarchive_global_alias, unpin_alias, unset_setting, update_global_alias,
+    upgrade_hook_if_outdated, upgrade_hooks, use_preset, use_recent_pairing, use_repo_roster,
+    uses_default_coauthors, velocity_report, verify_policy, which_alias, AddGlobalOptions,
+    AddOptions, AliasInfo, AmendOptions, AmendReport, AnnounceFormat, AuthProvider, BlameLine,
+    BranchPairing, CoauthorDiff, CommandSpec, DoctorCheck, DoctorStatus, ErrorCode, GitPairError,
+    GraphFormat, HistoryImportOptions, HookManager, HookTest, HookUpgrade, MobStartOptions,
+    PairTime, PairingCheck, PairingStats, PairingStreaks, PolicyReport, RecentPairing,
+    RosterBackup, RosterChanges, RosterConflict, RosterEntry, RosterImport, RosterMerge,
+    RosterMetadata, RosterSync, RosterSyncOptions, StandupGroup, StatsComparison, StatsFilter,
+    StatsWindow, TeamSync, TokenSource, TrailerStyle, UnpairedCommit, UserProvider,
+    VelocityInterval, DEFAULT_ADOPT_LIMIT, DEFAULT_AUDIT_LIMIT, DEFAULT_LOG_IMPORT_LIMIT,
+    DEFAULT_PROMPT_FORMAT, HEATMAP_CELLS, JSON_SCHEMA_VERSION,
 };
 use std::env;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
+/// Exit status of the run, set when an error is reported
+static EXIT_CODE: AtomicI32 = AtomicI32::new(0);
+/// Whether `--json` asked for errors, and the output of commands that support it, as JSON
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+/// Whether git is running the command as a hook, known once the global flags are read
+static HOOK_MODE: AtomicBool = AtomicBool::new(false);
+
+/// The commands, for completion and for checking their arguments before they run.
+/// `hook-impl`, which only old hooks call, is left out.
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "init",
+        subcommands: &[],
+        options: &["--template", "--with", "--from-default"],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "add",
+        subcommands: &[],
+        options: &[
+            "--global",
+            "--repo",
+            "--force",
+            "--update",
+            "--allow-duplicate-email",
+            "--display-name",
+            "--pronouns",
+            "--timezone",
+            "--github",
+            "--github-noreply",
+            "--gitlab",
+            "--host",
+            "--team",
+            "--interactive",
+            "--style",
+            "--trailer",
+            "--reviewer",
+            "--dry-run",
+            "--once",
+            "--for",
+        ],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "remove",
+        subcommands: &[],
+        options: &["--global", "--dry-run"],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "clear",
+        subcommands: &[],
+        options: &["--dry-run"],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "set-default",
+        subcommands: &[],
+        options: &["--clear"],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "status",
+        subcommands: &[],
+        options: &[
+            "--porcelain",
+            "--audit",
+            "--limit",
+            "--global",
+            "--repo",
+            "--repos",
+            "--branches",
+            "--all",
+        ],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "list",
+        subcommands: &[],
+        options: &[
+            "--porcelain",
+            "--audit",
+            "--limit",
+            "--global",
+            "--repo",
+            "--repos",
+            "--branches",
+            "--all",
+        ],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "driver",
+        subcommands: &["rotate"],
+        options: &["--reset"],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "pause",
+        subcommands: &[],
+        options: &[],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "solo",
+        subcommands: &[],
+        options: &[],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "resume",
+        subcommands: &[],
+        options: &[],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "snooze",
+        subcommands: &[],
+        options: &[],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "expire",
+        subcommands: &[],
+        options: &["--after", "--never"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "renew",
+        subcommands: &[],
+        options: &[],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "commit",
+        subcommands: &[],
+        options: &["--with"],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "once",
+        subcommands: &[],
+        options: &[],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "prompt",
+        subcommands: &[],
+        options: &["--starship", "--format"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "message",
+        subcommands: &[],
+        options: &[],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "enforce",
+        subcommands: &["on", "off", "enable", "disable"],
+        options: &["--branches"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "onboarding",
+        subcommands: &[],
+        options: &[],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "import",
+        subcommands: &[],
+        options: &[
+            "--github-org",
+            "--gitlab-group",
+            "--csv",
+            "--from-log",
+            "--team",
+            "--host",
+            "--limit",
+            "--dry-run",
+        ],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "import-history",
+        subcommands: &[],
+        options: &["--since", "--all-branches", "--repo", "--dry-run"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "team",
+        subcommands: &["sync"],
+        options: &["--github-org", "--gitlab-group", "--host", "--dry-run"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "archive",
+        subcommands: &[],
+        options: &["--global", "--unused", "--dry-run"],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "unarchive",
+        subcommands: &[],
+        options: &["--global"],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "footer",
+        subcommands: &[],
+        options: &["--clear"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "roster",
+        subcommands: &[
+            "lint", "dedupe", "sync", "push", "encrypt", "decrypt", "backup", "restore",
+        ],
+        options: &[
+            "--dry-run",
+            "--recipient",
+            "--key",
+            "--cert",
+            "--list",
+            "--from",
+            "--prefer",
+            "--to",
+        ],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "config",
+        subcommands: &[],
+        options: &["--unset"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "update",
+        subcommands: &[],
+        options: &["--global", "--name", "--email"],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "rename",
+        subcommands: &[],
+        options: &["--global"],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "which",
+        subcommands: &[],
+        options: &[],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "info",
+        subcommands: &[],
+        options: &[],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "pin",
+        subcommands: &[],
+        options: &["--clear"],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "stats",
+        subcommands: &[],
+        options: &[
+            "--author",
+            "--coauthor",
+            "--branch",
+            "--path",
+            "--since",
+            "--until",
+            "--csv",
+            "--time",
+            "--gap",
+            "--heatmap",
+            "--fun",
+            "--compare",
+            "--period",
+            "--since-a",
+            "--until-a",
+            "--since-b",
+            "--until-b",
+            "--report",
+            "--interval",
+        ],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "verify",
+        subcommands: &[],
+        options: &["--branch"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "check",
+        subcommands: &[],
+        options: &[],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "amend",
+        subcommands: &[],
+        options: &["--last", "--since", "--force", "--dry-run"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "adopt",
+        subcommands: &[],
+        options: &["--last", "--roster", "--dry-run"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "squash-msg",
+        subcommands: &[],
+        options: &["--dry-run"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "graph",
+        subcommands: &[],
+        options: &["--format"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "blame",
+        subcommands: &[],
+        options: &[],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "standup",
+        subcommands: &[],
+        options: &["--since"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "mob",
+        subcommands: &["start", "next", "status", "done"],
+        options: &["--minutes", "--message"],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "rotate",
+        subcommands: &[],
+        options: &["--schedule", "--date"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "recent",
+        subcommands: &[],
+        options: &["--use"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "save-set",
+        subcommands: &[],
+        options: &["--force"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "use",
+        subcommands: &[],
+        options: &[],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "group",
+        subcommands: &["list", "add", "remove", "apply"],
+        options: &["--force", "--dry-run", "--style"],
+        arguments: true,
+        aliases: true,
+    },
+    CommandSpec {
+        name: "announce",
+        subcommands: &[],
+        options: &["--format"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "share",
+        subcommands: &[],
+        options: &["--qr"],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "apply",
+        subcommands: &[],
+        options: &["--dry-run"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "events",
+        subcommands: &[],
+        options: &["--follow", "--new"],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "pr-body",
+        subcommands: &[],
+        options: &["--base", "--template"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "preview",
+        subcommands: &[],
+        options: &["--message"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "edit",
+        subcommands: &[],
+        options: &[],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "diff",
+        subcommands: &[],
+        options: &[],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "upgrade-hooks",
+        subcommands: &[],
+        options: &["--all"],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "install",
+        subcommands: &[],
+        options: &["--mode"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "install-hooks",
+        subcommands: &[],
+        options: &["--manager", "--apply"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "hook",
+        subcommands: &["run", "test"],
+        options: &["--message-file", "--source"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "setup-alias",
+        subcommands: &[],
+        options: &["--remove", "--force"],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "env",
+        subcommands: &[],
+        options: &[],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "cache",
+        subcommands: &["clear"],
+        options: &[],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "auth",
+        subcommands: &["login", "logout", "status"],
+        options: &[],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "check-config",
+        subcommands: &[],
+        options: &["--fix", "--dry-run"],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "doctor",
+        subcommands: &[],
+        options: &["--fix"],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "repair-hook",
+        subcommands: &[],
+        options: &[],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "clean-hooks",
+        subcommands: &[],
+        options: &["--dry-run"],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "prune",
+        subcommands: &[],
+        options: &["--dry-run"],
+        arguments: false,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "move",
+        subcommands: &[],
+        options: &[],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "completions",
+        subcommands: &["bash", "zsh", "fish", "powershell"],
+        options: &["--aliases"],
+        arguments: true,
+        aliases: false,
+    },
+    CommandSpec {
+        name: "help",
+        subcommands: &[],
+        options: &[],
+        arguments: true,
+        aliases: false,
+    },
+];
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    run();
+    // A git-pair problem must never stop a commit, so the hook commands always succeed
+    let exit_code = EXIT_CODE.load(Ordering::Relaxed);
+    if exit_code != 0 && !HOOK_MODE.load(Ordering::Relaxed) {
+        process::exit(exit_code);
+    }
+}
+
+fn run() {
+    let mut args: Vec<String> = env::args().collect();
+    // Everything from `--` on belongs to the command `once` runs, or to `git commit`
+    let passthrough = match args.iter().position(|arg| arg == "--") {
+        Some(separator) => args.split_off(separator),
+        None => Vec::new(),
+    };
+    // Global flags go before the command, and are passed on to everything git-pair runs
+    // (the hook included). After the command the same words are its own options, such
+    // as `add --repo`, or its arguments.
+    while let Some(flag) = args.get(1).cloned() {
+        match flag.as_str() {
+            "--offline" => env::set_var("GIT_PAIR_OFFLINE", "1"),
+            "--verbose" => env::set_var("GIT_PAIR_VERBOSE", "1"),
+            "--json" => JSON_OUTPUT.store(true, Ordering::Relaxed),
+            "--yes" | "-y" => env::set_var("GIT_PAIR_ASSUME_YES", "1"),
+            // `-C <path>` (or `--repo <path>`) works on another repository, as if git-pair
+            // was started there. Like git's, several of them resolve one after the other.
+            "-C" | "--repo" => {
+                if args.len() < 3 {
+                    usage(&["Usage: git-pair -C <path> <command>"]);
+                    return;
+                }
+                let path = args.remove(2);
+                if let Err(e) = env::set_current_dir(&path) {
+                    report_error(&GitPairError::io(format!("changing to '{}'", path), e));
+                    return;
+                }
+            }
+            _ => break,
+        }
+        args.remove(1);
+    }
+    // `hook test` isn't run by git, and reports failures like any other command
+    HOOK_MODE.store(
+        matches!(args.get(1).map(String::as_str), Some("hook" | "hook-impl"))
+            && args.get(2).map(String::as_str) != Some("test"),
+        Ordering::Relaxed,
+    );
+    // Options a command doesn't know, and arguments to one that takes none, are reported
+    // instead of ignored
+    if let Some(command) = args
+        .get(1)
+        .and_then(|name| COMMANDS.iter().find(|command| command.name == name))
+    {
+        if command.name != "help" && args[2..].iter().any(|arg| arg == "--help" || arg == "-h") {
+            print_command_help(command.name);
+            return;
+        }
+        if let Some(problem) = command.check_arguments(&args[2..]) {
+            usage(&[
+                &problem,
+                &format!(
+                    "Use 'git-pair {} --help' for usage information.",
+                    command.name
+                ),
+            ]);
+            return;
+        }
+    }
+    let dry_run = args.iter().skip(2).any(|arg| arg == "--dry-run");
+    args.extend(passthrough);
 
     if args.len() > 1 {
+        // Keep the hook in step with this binary; failures are reported by `status`.
+        // Not while git is running the hook itself, which must not be rewritten mid-run.
+        // Nor for `prompt`, which must stay fast, or a dry run, which writes nothing.
+        if args[1] != "hook" && args[1] != "hook-impl" && args[1] != "prompt" && !dry_run {
+            let _ = upgrade_hook_if_outdated();
+        }
+
         match args[1].as_str() {
             "--version" | "-V" => {
                 println!("git-pair {}", env!("CARGO_PKG_VERSION"));
             }
-            "--help" | "-h" | "help" => {
-                print_help();
-            }
-            "init" => match init_pair_config() {
-                Ok(message) => println!("{}", message),
-                Err(e) => eprintln!("Error: {}", e),
+            "--help" | "-h" | "help" => match args.get(2) {
+                Some(command) => print_command_help(command),
+                None => print_help(),
             },
+            "--json-schema" => match args.get(2) {
+                Some(command) if args.len() == 3 => match json_schema(command) {
+                    Ok(schema) => print!("{}", schema),
+                    Err(e) => report_error(&e),
+                },
+                _ => usage(&[
+                    "Usage: git-pair --json-schema <command>",
+                    "Prints the JSON Schema for what 'git-pair --json <command>' writes",
+                ]),
+            },
+            "init" => {
+                let mut init_args: Vec<String> = args[2..].to_vec();
+                let template = take_option(&mut init_args, "--template");
+                // `--with` takes the rest of the arguments as aliases, or picks from the roster
+                let with = take_flag(&mut init_args, "--with");
+                let from_default = take_flag(&mut init_args, "--from-default");
+                let interactive = io::stdin().is_terminal();
+                if (!with && !init_args.is_empty())
+                    || (with && init_args.is_empty() && !interactive)
+                    || (from_default && template.is_some())
+                {
+                    usage(&[
+                        "Usage: git-pair init [--template <file> | --from-default] [--with [<alias>...]]",
+                        "Without aliases, --with lets you pick co-authors from the roster in a terminal",
+                    ]);
+                    return;
+                }
+                let result = if from_default {
+                    init_from_default()
+                } else {
+                    init_pair_config_with(template.as_deref().map(Path::new))
+                };
+                match result {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => return report_error(&e),
+                }
+                if with {
+                    let aliases = if init_args.is_empty() {
+                        pick_roster_aliases()
+                    } else {
+                        Ok(init_args
+                            .iter()
+                            .flat_map(|arg| arg.split(','))
+                            .filter(|alias| !alias.is_empty())
+                            .map(str::to_string)
+                            .collect())
+                    };
+                    match aliases {
+                        Ok(aliases) if aliases.is_empty() => println!("Nobody added"),
+                        Ok(aliases) => add_aliases(&aliases, &AddOptions::default()),
+                        Err(e) => report_error(&e),
+                    }
+                }
+            }
             "add" => {
-                if args.len() >= 3 && args[2] == "--global" {
+                if args.len() >= 3 && (args[2] == "--global" || args[2] == "--repo") {
                     // Global add: git pair add --global alice "Alice Johnson" alice@company.com
-                    if args.len() >= 6 {
-                        let alias = &args[3];
-                        let name = &args[4];
-                        let email = &args[5];
-                        match add_global_coauthor(alias, name, email) {
-                            Ok(message) => println!("{}", message),
-                            Err(e) => eprintln!("Error: {}", e),
+                    // or to the committed repository roster with --repo
+                    let mut global_args: Vec<String> = args[3..].to_vec();
+                    let metadata = RosterMetadata {
+                        display_name: take_option(&mut global_args, "--display-name"),
+                        pronouns: take_option(&mut global_args, "--pronouns"),
+                        timezone: take_option(&mut global_args, "--timezone"),
+                    };
+                    let options = AddGlobalOptions {
+                        allow_duplicate_email: global_args
+                            .iter()
+                            .any(|a| a == "--allow-duplicate-email"),
+                        force: global_args.iter().any(|a| a == "--force" || a == "--update"),
+                        metadata,
+                    };
+                    let github = take_option(&mut global_args, "--github");
+                    let gitlab = take_option(&mut global_args, "--gitlab");
+                    let host = take_option(&mut global_args, "--host");
+                    let positional: Vec<&String> =
+                        global_args.iter().filter(|a| !a.starts_with("--")).collect();
+                    let provider = match (github, gitlab) {
+                        (Some(username), None) => Some((UserProvider::GitHub, username)),
+                        (None, Some(username)) => {
+                            Some((UserProvider::gitlab(host.as_deref()), username))
+                        }
+                        _ => None,
+                    };
+                    if let Some((provider, username)) = provider {
+                        // The alias defaults to the lowercased username
+                        let alias = positional
+                            .first()
+                            .map_or_else(|| username.to_lowercase(), |alias| alias.to_string());
+                        let user = lookup_provider_user(&provider, &username);
+                        let result = user.and_then(|user| {
+                            let (name, email) = (&user.name, &user.email);
+                            if args[2] == "--repo" {
+                                add_repo_coauthor_with(&alias, name, email, &options)
+                            } else {
+                                add_global_coauthor_with(&alias, name, email, &options)
+                            }
+                        });
+                        match result {
+                            Ok(message) => print_message(&message),
+                            Err(e) => report_error(&e),
+                        }
+                    } else if positional.len() >= 2 {
+                        let alias = positional[0];
+                        let name = positional[1];
+                        // Without an email the roster keeps a placeholder
+                        let email = positional.get(2).map_or("", |e| e.as_str());
+                        let result = if args[2] == "--repo" {
+                            add_repo_coauthor_with(alias, name, email, &options)
+                        } else {
+                            add_global_coauthor_with(alias, name, email, &options)
+                        };
+                        match result {
+                            Ok(message) => print_message(&message),
+                            Err(e) => report_error(&e),
                         }
                     } else {
-                        eprintln!("Usage: git-pair add --global <alias> <name> <email>");
-                    }
-                } else if args.len() >= 5 {
-                    // Direct add with name, surname, email
-                    let name = &args[2];
-                    let surname = &args[3];
-                    let email = &args[4];
-                    match add_coauthor(name, surname, email) {
-                        Ok(message) => println!("{}", message),
-                        Err(e) => eprintln!("Error: {}", e),
+                        usage(&[
+                            "Usage: git-pair add --global|--repo <alias> <name> [<email>] [--force] [--allow-duplicate-email]",
+                            "       [--display-name <name>] [--pronouns <pronouns>] [--timezone <zone>]",
+                            "   or: git-pair add --global|--repo --github <username> [<alias>]",
+                            "   or: git-pair add --global|--repo --gitlab <username> [--host <host>] [<alias>]",
+                        ]);
                     }
-                } else if args.len() == 3 {
-                    // Quick add from roster using alias
-                    let alias = &args[2];
-                    match add_coauthor_from_global(alias) {
+                } else {
+                    let mut add_args: Vec<String> = args[2..].to_vec();
+                    let dry_run = take_flag(&mut add_args, "--dry-run");
+                    let once = take_flag(&mut add_args, "--once");
+                    let force = take_flag(&mut add_args, "--force");
+                    let reviewer = take_flag(&mut add_args, "--reviewer");
+                    let style = take_option(&mut add_args, "--style")
+                        .or_else(|| take_option(&mut add_args, "--trailer"))
+                        .or_else(|| reviewer.then(|| "reviewed-by".to_string()));
+                    let expires_in = match take_option(&mut add_args, "--for")
+                        .map(|duration| parse_duration(&duration))
+                        .transpose()
+                    {
+                        Ok(expires_in) => expires_in,
+                        Err(e) => {
+                            report_error(&e);
+                            return;
+                        }
+                    };
+                    let options = match style
+                        .map(|style| TrailerStyle::parse(&style))
+                        .transpose()
+                    {
+                        Ok(style) => AddOptions {
+                            style: style.unwrap_or_default(),
+                            dry_run,
+                            once,
+                            force,
+                            expires_in,
+                        },
+                        Err(e) => {
+                            report_error(&e);
+                            return;
+                        }
+                    };
+
+                    let team = take_option(&mut add_args, "--team");
+                    let host = take_option(&mut add_args, "--host");
+                    let provider_flags = ["--github", "--github-noreply", "--gitlab"];
+                    if let Some(team) = team.filter(|_| add_args.is_empty()) {
+                        match team_aliases(&team) {
+                            Ok(aliases) => add_aliases(&aliases, &options),
+                            Err(e) => report_error(&e),
+                        }
+                    } else if let Some(index) = add_args
+                        .iter()
+                        .position(|a| a == "--interactive" || a == "-i")
+                    {
+                        add_args.remove(index);
+                        let mut roster = get_roster().unwrap_or_default();
+                        let archived = get_archived_aliases().unwrap_or_default();
+                        roster.retain(|(alias, _, _)| !archived.contains(alias));
+                        let alias = if roster.is_empty() {
+                            Ok(String::new())
+                        } else {
+                            let coauthors = get_coauthors().unwrap_or_default();
+                            prompt_alias(
+                                "Alias (Tab completes, Enter alone to type details)",
+                                &roster,
+                                &coauthors,
+                            )
+                        };
+                        let result = match alias {
+                            Ok(alias) if !alias.is_empty() => {
+                                add_coauthor_from_global_with(&alias, &options)
+                            }
+                            Ok(_) => prompt_coauthor().and_then(|(name, surname, email)| {
+                                add_coauthor_with(&name, &surname, &email, &options)
+                            }),
+                            Err(e) => Err(e),
+                        };
+                        match result {
+                            Ok(message) => print_change(&message),
+                            Err(e) => report_error(&e),
+                        }
+                    } else if let Some(index) = add_args
+                        .iter()
+                        .position(|a| provider_flags.contains(&a.as_str()))
+                    {
+                        let provider = match add_args.remove(index).as_str() {
+                            "--gitlab" => UserProvider::gitlab(host.as_deref()),
+                            _ => UserProvider::GitHub,
+                        };
+                        match add_args.get(index) {
+                            Some(username) => {
+                                let display_name = add_args.get(index + 1).map(String::as_str);
+                                match add_coauthor_from_provider(
+                                    &provider,
+                                    username,
+                                    display_name,
+                                    &options,
+                                ) {
+                                    Ok(message) => print_change(&message),
+                                    Err(e) => report_error(&e),
+                                }
+                            }
+                            None => usage(&[
+                                "Usage: git-pair add --github <username> [\"Display Name\"]",
+                                "   or: git-pair add --gitlab <username> [--host <host>] [\"Display Name\"]",
+                            ]),
+                        }
+                    } else if add_args.is_empty() && io::stdin().is_terminal() {
+                        match pick_roster_aliases() {
+                            Ok(aliases) if aliases.is_empty() => println!("Nobody added"),
+                            Ok(aliases) => add_aliases(&aliases, &options),
+                            Err(e) => report_error(&e),
+                        }
+                    } else if add_args.len() >= 3 {
+                        // Direct add with name, surname, email
+                        let name = &add_args[0];
+                        let surname = &add_args[1];
+                        let email = &add_args[2];
+                        match add_coauthor_with(name, surname, email, &options) {
+                            Ok(message) => print_change(&message),
+                            Err(e) => report_error(&e),
+                        }
+                    } else if add_args.len() == 1 {
+                        // Quick add from roster using alias
+                        let alias = &add_args[0];
+                        match add_coauthor_from_global_with(alias, &options) {
+                            Ok(message) => print_change(&message),
+                            Err(e) => report_error(&e),
+                        }
+                    } else {
+                        usage(&[
+                            "Usage: git-pair add <name> <surname> <email> [--style <style>] [--once] [--force]",
+                            "   or: git-pair add <alias> [--style <style>] [--once]",
+                            "   or: git-pair add <alias> --for <duration>",
+                            "   or: git-pair add <alias> --trailer signed-off-by|reviewed-by",
+                            "   or: git-pair add <alias> --reviewer",
+                            "   or: git-pair add    (pick from the roster, in a terminal)",
+                            "   or: git-pair add --interactive [--style <style>]",
+                            "   or: git-pair add @<group> [--style <style>]",
+                            "   or: git-pair add --team <slug> [--style <style>]",
+                            "   or: git-pair add --github <username> [\"Display Name\"]",
+                            "   or: git-pair add --gitlab <username> [--host <host>] [\"Display Name\"]",
+                            "   or: git-pair add --global <alias> <name> <email>",
+                        ]);
+                    }
+                }
+            }
+            "driver" => match args.get(2).map(String::as_str) {
+                None => match get_driver() {
+                    Ok(Some(driver)) => println!("{} <{}> is driving", driver.name, driver.email),
+                    Ok(None) => println!("No driver set on this branch"),
+                    Err(e) => report_error(&e),
+                },
+                Some(command) if args.len() == 3 => {
+                    let result = match command {
+                        "rotate" => rotate_driver(),
+                        "--reset" => reset_driver(),
+                        alias => set_driver(alias),
+                    };
+                    match result {
                         Ok(message) => println!("{}", message),
-                        Err(e) => eprintln!("Error: {}", e),
+                        Err(e) => report_error(&e),
                     }
+                }
+                Some(_) => usage(&[
+                    "Usage: git-pair driver [<alias>|rotate|--reset]",
+                    "Makes a roster member the commit author, turning the previous one into a co-author",
+                ]),
+            },
+            "pause" | "solo" => match pause_pairing() {
+                Ok(message) => println!("{}", message),
+                Err(e) => report_error(&e),
+            },
+            "resume" => match resume_pairing() {
+                Ok(message) => println!("{}", message),
+                Err(e) => report_error(&e),
+            },
+            "snooze" => match args.get(2) {
+                None => match snoozed_until() {
+                    Ok(Some(until)) => println!("Snoozed until {}", until),
+                    Ok(None) => println!("Not snoozed"),
+                    Err(e) => report_error(&e),
+                },
+                Some(duration) if args.len() == 3 => match snooze(duration) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                },
+                Some(_) => usage(&[
+                    "Usage: git-pair snooze [<duration>]",
+                    "Stops adding co-authors until the duration (e.g. 2h, 45m, 1d) is over",
+                ]),
+            },
+            "expire" => match &args[2..] {
+                [flag, duration] if flag == "--after" => match expire_coauthors(Some(duration)) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                },
+                [flag] if flag == "--never" => match expire_coauthors(None) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                },
+                _ => usage(&[
+                    "Usage: git-pair expire --after <duration>",
+                    "       git-pair expire --never",
+                    "Stops crediting everyone on the branch once the duration (e.g. 8h, 1d) is over",
+                ]),
+            },
+            "renew" => match &args[2..] {
+                [] => match renew_coauthors(None) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                },
+                [duration] => match renew_coauthors(Some(duration)) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                },
+                _ => usage(&[
+                    "Usage: git-pair renew [<duration>]",
+                    "Extends the expiry of the branch's co-authors by the duration, or the last one given",
+                ]),
+            },
+            "commit" => {
+                let separator = args.iter().position(|arg| arg == "--");
+                let mut commit_args: Vec<String> = args[2..separator.unwrap_or(args.len())].to_vec();
+                let mut aliases = Vec::new();
+                while let Some(alias) = take_option(&mut commit_args, "--with") {
+                    aliases.push(alias);
+                }
+                if aliases.is_empty() || !commit_args.is_empty() {
+                    usage(&[
+                        "Usage: git-pair commit --with <alias>... [-- <git commit arguments>...]",
+                        "Commits crediting those co-authors on this commit only, leaving the branch's config alone",
+                        "Example:",
+                        "  git-pair commit --with alice --with bob -- -m \"Fix login\"",
+                    ]);
                 } else {
-                    eprintln!("Usage: git-pair add <name> <surname> <email>");
-                    eprintln!("   or: git-pair add <alias>");
-                    eprintln!("   or: git-pair add --global <alias> <name> <email>");
+                    let git_args = separator.map_or(&[][..], |separator| &args[separator + 1..]);
+                    match commit_with(&aliases, git_args) {
+                        Ok(code) => EXIT_CODE.store(code, Ordering::Relaxed),
+                        Err(e) => report_error(&e),
+                    }
                 }
             }
-            "clear" => match clear_coauthors() {
-                Ok(message) => println!("{}", message),
-                Err(e) => eprintln!("Error: {}", e),
+            "once" => match args.iter().position(|arg| arg == "--") {
+                Some(separator) if separator > 2 && separator + 1 < args.len() => {
+                    match run_once(&args[2..separator], &args[separator + 1..]) {
+                        Ok(code) => EXIT_CODE.store(code, Ordering::Relaxed),
+                        Err(e) => report_error(&e),
+                    }
+                }
+                _ => usage(&[
+                    "Usage: git-pair once <alias>... -- <command>...",
+                    "Runs the command with those co-authors credited instead of the branch's",
+                    "Example:",
+                    "  git-pair once alice bob -- git commit -m \"Fix login\"",
+                ]),
+            },
+            "prompt" => {
+                let mut prompt_args: Vec<String> = args[2..].to_vec();
+                let starship = take_flag(&mut prompt_args, "--starship");
+                let format = take_option(&mut prompt_args, "--format");
+                if !prompt_args.is_empty() {
+                    usage(&[
+                        "Usage: git-pair prompt [--starship] [--format <format>]",
+                        "Prints who you're pairing with, or nothing when solo",
+                        "The format can use {names}, {initials}, {count} and {paused}, e.g. '\u{26af} {initials}'",
+                    ]);
+                // Runs on every prompt: never fail it, and stay quiet outside git-pair repos
+                } else if let Ok(Some(segment)) =
+                    prompt_segment_with(format.as_deref().unwrap_or(DEFAULT_PROMPT_FORMAT))
+                {
+                    if starship {
+                        print!("{}", segment);
+                    } else {
+                        println!("{}", segment);
+                    }
+                }
+            }
+            "message" => {
+                if args.len() > 2 {
+                    usage(&[
+                        "Usage: git-pair message",
+                        "Prints the co-author trailers for the next commit, e.g.",
+                        "  git commit -m \"msg\" -m \"$(git pair message)\"",
+                    ]);
+                } else {
+                    match commit_message_trailers() {
+                        Ok(trailers) => print!("{}", trailers),
+                        Err(e) => report_error(&e),
+                    }
+                }
+            }
+            "enforce" => match args.get(2).map(String::as_str) {
+                None => match is_enforcing().and_then(|on| Ok((on, enforce_branches()?))) {
+                    Ok((true, branches)) if !branches.is_empty() => println!(
+                        "Enforcement is on, with a co-author from the roster required on {}",
+                        branches.join(", ")
+                    ),
+                    Ok((on, _)) => println!("Enforcement is {}", if on { "on" } else { "off" }),
+                    Err(e) => report_error(&e),
+                },
+                Some("enable") => {
+                    let mut enforce_args: Vec<String> = args[3..].to_vec();
+                    let mut branches = Vec::new();
+                    while let Some(pattern) = take_option(&mut enforce_args, "--branches") {
+                        branches.push(pattern);
+                    }
+                    if !enforce_args.is_empty() {
+                        usage(&["Usage: git-pair enforce enable [--branches <pattern>]..."]);
+                        return;
+                    }
+                    match enable_enforcement(&branches) {
+                        Ok(message) => println!("{}", message),
+                        Err(e) => report_error(&e),
+                    }
+                }
+                Some(mode @ ("on" | "off" | "disable")) if args.len() == 3 => {
+                    match set_enforcement(mode == "on") {
+                        Ok(message) => println!("{}", message),
+                        Err(e) => report_error(&e),
+                    }
+                }
+                Some(_) => usage(&[
+                    "Usage: git-pair enforce [on|off|enable [--branches <pattern>]...|disable]",
+                    "With enforcement on, commits on a paired branch without its co-author trailers are rejected",
+                    "With --branches, commits on matching branches also need a co-author from the roster",
+                ]),
             },
+            "archive" if args.get(2).map(String::as_str) == Some("--global") => {
+                let mut archive_args: Vec<String> = args[3..].to_vec();
+                let dry_run = take_flag(&mut archive_args, "--dry-run");
+                if let Some(months) = take_option(&mut archive_args, "--unused") {
+                    let Some(months) = months.parse::<u64>().ok().filter(|m| *m > 0) else {
+                        report(
+                            ErrorCode::InvalidValue,
+                            "--unused must be a positive number of months",
+                        );
+                        return;
+                    };
+                    match archive_unused_aliases(months, dry_run) {
+                        Ok(entries) if entries.is_empty() => {
+                            println!("Nobody in the roster has gone unused for {} months", months)
+                        }
+                        Ok(entries) => {
+                            println!(
+                                "{} entries unused for {} months:",
+                                if dry_run { "Would archive" } else { "Archived" },
+                                months
+                            );
+                            for (alias, name, _) in entries {
+                                println!("  {} -> {}", alias, name);
+                            }
+                        }
+                        Err(e) => report_error(&e),
+                    }
+                } else if !archive_args.is_empty() && !dry_run {
+                    let aliases: Vec<&str> = archive_args.iter().map(String::as_str).collect();
+                    match archive_global_aliases(&aliases) {
+                        Ok(message) => println!("{}", message),
+                        Err(e) => report_error(&e),
+                    }
+                } else {
+                    usage(&[
+                        "Usage: git-pair archive --global <alias>...",
+                        "       git-pair archive --global --unused <months> [--dry-run]",
+                    ]);
+                }
+            }
+            "onboarding" => {
+                if args.len() > 2 {
+                    usage(&["Usage: git-pair onboarding"]);
+                    return;
+                }
+                if let Err(e) = run_onboarding() {
+                    report_error(&e);
+                }
+            }
+            "import" => {
+                let mut import_args: Vec<String> = args[2..].to_vec();
+                let dry_run = take_flag(&mut import_args, "--dry-run");
+                let team = take_option(&mut import_args, "--team");
+                let host = take_option(&mut import_args, "--host");
+                let org = take_option(&mut import_args, "--github-org");
+                let group = take_option(&mut import_args, "--gitlab-group");
+                let csv = take_option(&mut import_args, "--csv");
+                let from_log = take_flag(&mut import_args, "--from-log");
+                let limit = match take_option(&mut import_args, "--limit") {
+                    None => Some(DEFAULT_LOG_IMPORT_LIMIT),
+                    Some(limit) => limit.parse::<usize>().ok().filter(|n| *n > 0 && from_log),
+                };
+                let sources = [org.is_some(), group.is_some(), csv.is_some(), from_log];
+                let valid = sources.iter().filter(|source| **source).count() == 1
+                    && import_args.is_empty()
+                    && limit.is_some()
+                    && (team.is_none() || org.is_some())
+                    && (host.is_none() || group.is_some());
+                let result = match (org, group, csv, limit) {
+                    _ if !valid => None,
+                    (Some(org), ..) => Some(import_github_org(&org, team.as_deref(), dry_run)),
+                    (_, Some(group), ..) => {
+                        Some(import_gitlab_group(&group, host.as_deref(), dry_run))
+                    }
+                    (.., Some(csv), _) => Some(import_roster_csv(Path::new(&csv), dry_run)),
+                    (.., Some(limit)) => Some(import_repo_authors(limit, dry_run)),
+                    _ => None,
+                };
+                let Some(result) = result else {
+                    usage(&[
+                        "Usage: git-pair import --github-org <org> [--team <slug>] [--dry-run]",
+                        "       git-pair import --gitlab-group <group> [--host <host>] [--dry-run]",
+                        "       git-pair import --csv <file> [--dry-run]",
+                        "       git-pair import --from-log [--limit <n>] [--dry-run]",
+                        "Private memberships need a token: 'git-pair auth login github' or 'gitlab'",
+                    ]);
+                    return;
+                };
+                match result {
+                    Ok(import) => print_roster_import(&import, dry_run, "global roster"),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "import-history" => {
+                let mut history_args: Vec<String> = args[2..].to_vec();
+                let mut options = HistoryImportOptions {
+                    since: take_option(&mut history_args, "--since"),
+                    all_branches: take_flag(&mut history_args, "--all-branches"),
+                    repo_roster: take_flag(&mut history_args, "--repo"),
+                    dry_run: true,
+                };
+                let dry_run = take_flag(&mut history_args, "--dry-run");
+                if !history_args.is_empty() {
+                    usage(&[
+                        "Usage: git-pair import-history [--since <when>] [--all-branches] [--repo] [--dry-run]",
+                        "Adds the authors, committers and co-authors in the history to the global roster",
+                    ]);
+                    return;
+                }
+                let roster = if options.repo_roster {
+                    "repository roster"
+                } else {
+                    "global roster"
+                };
+                // Show who would be added, then add them once confirmed
+                let planned = match import_history(&options) {
+                    Ok(planned) => planned,
+                    Err(e) => return report_error(&e),
+                };
+                print_roster_import(&planned, true, roster);
+                if dry_run || planned.added.is_empty() {
+                    return;
+                }
+                options.dry_run = false;
+                let result = confirm("Add them?").and_then(|confirmed| {
+                    confirmed.then(|| import_history(&options)).transpose()
+                });
+                match result {
+                    Ok(Some(import)) => println!(
+                        "Added {} people to the {}",
+                        import.added.len(),
+                        roster
+                    ),
+                    Ok(None) => println!("Nothing added"),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "team" => {
+                let mut team_args: Vec<String> = args[2..].to_vec();
+                let dry_run = take_flag(&mut team_args, "--dry-run");
+                let host = take_option(&mut team_args, "--host");
+                let org = take_option(&mut team_args, "--github-org");
+                let group = take_option(&mut team_args, "--gitlab-group");
+                let result = match (team_args.as_slice(), org, group) {
+                    ([sync], Some(org), None) if sync == "sync" && host.is_none() => {
+                        Some(sync_github_teams(&org, dry_run))
+                    }
+                    ([sync], None, Some(group)) if sync == "sync" => {
+                        Some(sync_gitlab_teams(&group, host.as_deref(), dry_run))
+                    }
+                    _ => None,
+                };
+                match result {
+                    Some(Ok(sync)) => print_team_sync(&sync, dry_run),
+                    Some(Err(e)) => report_error(&e),
+                    None => usage(&[
+                        "Usage: git-pair team sync --github-org <org> [--dry-run]",
+                        "       git-pair team sync --gitlab-group <group> [--host <host>] [--dry-run]",
+                        "Tags roster entries with team:<slug> for their teams, for 'git-pair add --team <slug>'",
+                    ]),
+                }
+            }
+            "unarchive" if args.len() == 4 && args[2] == "--global" => {
+                match unarchive_global_alias(&args[3]) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "archive" | "unarchive" => usage(&[
+                "Usage: git-pair archive --global <alias>...",
+                "       git-pair archive --global --unused <months> [--dry-run]",
+                "       git-pair unarchive --global <alias>",
+            ]),
+            "footer" => {
+                let mut footer_args: Vec<String> = args[2..].to_vec();
+                let clear = take_flag(&mut footer_args, "--clear");
+                match (clear, footer_args.as_slice()) {
+                    (true, []) => match set_footer(None) {
+                        Ok(message) => println!("{}", message),
+                        Err(e) => report_error(&e),
+                    },
+                    (false, []) => match get_footer() {
+                        Ok(Some(footer)) => println!("{}", footer),
+                        Ok(None) => println!("No footer on this branch"),
+                        Err(e) => report_error(&e),
+                    },
+                    (false, [text]) => match set_footer(Some(text)) {
+                        Ok(message) => println!("{}", message),
+                        Err(e) => report_error(&e),
+                    },
+                    _ => usage(&[
+                        "Usage: git-pair footer [<text>|--clear]",
+                        "{branch} and {ticket} are filled in when the hook adds it",
+                        "Examples:",
+                        "  git-pair footer \"Refs: {ticket}\"",
+                        "  git-pair footer --clear",
+                    ]),
+                }
+            }
+            "clear" => {
+                let dry_run = args[2..].iter().any(|a| a == "--dry-run");
+                match clear_coauthors_with(dry_run) {
+                    Ok(message) => print_change(&message),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "set-default" => {
+                let result = match &args[2..] {
+                    [] => set_default_coauthors(),
+                    [flag] if flag == "--clear" => clear_default_coauthors(),
+                    _ => {
+                        usage(&[
+                            "Usage: git-pair set-default [--clear]",
+                            "Makes the current branch's co-authors the default for branches without a config",
+                        ]);
+                        return;
+                    }
+                };
+                match result {
+                    Ok(message) => print_message(&message),
+                    Err(e) => report_error(&e),
+                }
+            }
             "remove" => {
-                if args.len() >= 3 {
-                    let identifier = &args[2];
-                    match remove_coauthor(identifier) {
+                let mut remove_args: Vec<String> = args[2..].to_vec();
+                let dry_run = take_flag(&mut remove_args, "--dry-run");
+                let global = take_flag(&mut remove_args, "--global");
+                if let (true, [alias], false) = (global, remove_args.as_slice(), dry_run) {
+                    match remove_global_alias(alias) {
+                        Ok(message) => println!("{}", message),
+                        Err(e) => report_error(&e),
+                    }
+                } else if global {
+                    usage(&["Usage: git-pair remove --global <alias>"]);
+                } else if let Some(identifier) = remove_args.first() {
+                    match remove_coauthor_with(identifier, dry_run) {
+                        Ok(message) => print_change(&message),
+                        Err(e) => report_error(&e),
+                    }
+                } else {
+                    usage(&[
+                        "Usage: git-pair remove <name|email|alias> [--dry-run]",
+                        "       git-pair remove --global <alias>",
+                        "Examples:",
+                        "  git-pair remove \"John Doe\"",
+                        "  git-pair remove john.doe@example.com",
+                        "  git-pair remove alice",
+                    ]);
+                }
+            }
+            "roster" => match args.get(2).map(|s| s.as_str()) {
+                Some("lint") => match lint_global_roster().and_then(|problems| {
+                    roster_conflicts().map(|conflicts| (problems, conflicts))
+                }) {
+                    Ok((problems, (conflicts, pin_problems))) => {
+                        if problems.is_empty() {
+                            println!("No problems found in global roster");
+                        } else {
+                            println!("Found {} problem(s) in global roster:", problems.len());
+                            for problem in &problems {
+                                println!("  {}", problem);
+                            }
+                        }
+                        print_roster_conflicts(&conflicts, &pin_problems);
+                        // Conflicts resolve the same way every time, so only real
+                        // problems fail the lint
+                        let found = problems.len() + pin_problems.len();
+                        if found > 0 {
+                            report(
+                                ErrorCode::InvalidValue,
+                                &format!("Roster lint found {} problem(s)", found),
+                            );
+                        }
+                    }
+                    Err(e) => report_error(&e),
+                },
+                Some("dedupe") => {
+                    let dry_run = args[3..].iter().any(|a| a == "--dry-run");
+                    // Merging rewrites the shared roster, so the merges are shown and
+                    // confirmed first
+                    let result = plan_roster_dedupe().and_then(|plan| {
+                        if plan.merges.is_empty() {
+                            println!("No duplicate entries found in global roster");
+                            return Ok(());
+                        }
+                        print_roster_merges("Would merge", &plan.merges);
+                        if dry_run || !confirm("Merge these entries?")? {
+                            return Ok(());
+                        }
+                        apply_roster_dedupe(&plan)?;
+                        print_roster_merges("Merged", &plan.merges);
+                        Ok(())
+                    });
+                    if let Err(e) = result {
+                        report_error(&e);
+                    }
+                }
+                Some("encrypt") => {
+                    let mut encrypt_args: Vec<String> = args[3..].to_vec();
+                    let mut recipients = Vec::new();
+                    while let Some(recipient) = take_option(&mut encrypt_args, "--recipient")
+                        .or_else(|| take_option(&mut encrypt_args, "-r"))
+                    {
+                        recipients.push(recipient);
+                    }
+                    match encrypt_global_roster(&recipients) {
+                        Ok(message) => println!("{}", message),
+                        Err(e) => report_error(&e),
+                    }
+                }
+                Some("decrypt") => match decrypt_global_roster() {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                },
+                Some("backup") => match backup_global_roster() {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                },
+                Some("restore") => {
+                    let mut restore_args: Vec<String> = args[3..].to_vec();
+                    let list = take_flag(&mut restore_args, "--list");
+                    if restore_args.len() > 1 {
+                        usage(&["Usage: git-pair roster restore [<backup>] [--list]"]);
+                    }
+                    let name = restore_args.first().map(|s| s.as_str());
+                    let result = roster_backups().and_then(|backups| {
+                        if list {
+                            print_roster_backups(&backups);
+                            return Ok(());
+                        }
+                        // Restoring replaces the whole roster, so it's confirmed first
+                        let target = name.or(backups.first().map(|b| b.name.as_str()));
+                        if let Some(target) = target {
+                            if !confirm(&format!("Replace the roster with backup {}?", target))? {
+                                return Ok(());
+                            }
+                        }
+                        println!("{}", restore_global_roster(name)?);
+                        Ok(())
+                    });
+                    if let Err(e) = result {
+                        report_error(&e);
+                    }
+                }
+                Some("sync") if args.iter().any(|arg| arg == "--from") => {
+                    let mut sync_args: Vec<String> = args[3..].to_vec();
+                    let source = take_option(&mut sync_args, "--from");
+                    let prefer = take_option(&mut sync_args, "--prefer");
+                    let dry_run = take_flag(&mut sync_args, "--dry-run");
+                    let Some(source) = source.filter(|_| sync_args.is_empty()) else {
+                        usage(&["Usage: git-pair roster sync --from <source> [--prefer remote|local|ask] [--dry-run]"]);
+                        return;
+                    };
+                    let result = match prefer.as_deref().unwrap_or("remote") {
+                        "remote" => pull_roster(&source, |_, _| Ok(true), dry_run),
+                        "local" => pull_roster(&source, |_, _| Ok(false), dry_run),
+                        "ask" => pull_roster(
+                            &source,
+                            |(alias, name, email), (_, shared_name, shared_email)| {
+                                confirm(&format!(
+                                    "'{}' is {} <{}> in your roster and {} <{}> in {}. Take the shared entry?",
+                                    alias, name, email, shared_name, shared_email, source
+                                ))
+                            },
+                            dry_run,
+                        ),
+                        other => {
+                            report(
+                                ErrorCode::InvalidValue,
+                                &format!(
+                                    "Invalid --prefer '{}'. Use 'remote', 'local' or 'ask'.",
+                                    other
+                                ),
+                            );
+                            return;
+                        }
+                    };
+                    match result {
+                        Ok(changes) => print_roster_changes(
+                            &changes,
+                            if dry_run { "Would sync" } else { "Synced" },
+                        ),
+                        Err(e) => report_error(&e),
+                    }
+                }
+                Some("push") => {
+                    let mut push_args: Vec<String> = args[3..].to_vec();
+                    let to = take_option(&mut push_args, "--to");
+                    if !push_args.is_empty() {
+                        usage(&["Usage: git-pair roster push [--to <git remote>]"]);
+                        return;
+                    }
+                    match push_roster(to.as_deref()) {
+                        Ok(changes) => print_roster_changes(&changes, "Pushed"),
+                        Err(e) => report_error(&e),
+                    }
+                }
+                Some("sync") => {
+                    let mut sync_args: Vec<String> = args[3..].to_vec();
+                    let cert = take_option(&mut sync_args, "--cert").map(PathBuf::from);
+                    let key = take_option(&mut sync_args, "--key").map(PathBuf::from);
+                    let options = RosterSyncOptions {
+                        url: sync_args.first().cloned(),
+                        cert,
+                        key,
+                    };
+                    match sync_roster(&options) {
+                        Ok(sync) => print_roster_sync(&sync),
+                        Err(e) => report_error(&e),
+                    }
+                }
+                _ => {
+                    usage(&[
+                        "Usage: git-pair roster lint",
+                        "   or: git-pair roster dedupe [--dry-run]",
+                        "   or: git-pair roster backup",
+                        "   or: git-pair roster restore [<backup>] [--list]",
+                        "   or: git-pair roster sync [<url>] [--cert <file>] [--key <file>]",
+                        "   or: git-pair roster sync --from <source> [--prefer remote|local|ask] [--dry-run]",
+                        "   or: git-pair roster push [--to <git remote>]",
+                        "   or: git-pair roster encrypt [--recipient <age key>]...",
+                        "   or: git-pair roster decrypt",
+                    ]);
+                }
+            },
+            "config" => match args.len() {
+                2 => match get_settings() {
+                    Ok(settings) => {
+                        if settings.is_empty() {
+                            println!("No settings configured for this repository (using defaults)");
+                        } else {
+                            for (key, value) in settings {
+                                println!("{}={}", key, value);
+                            }
+                        }
+                    }
+                    Err(e) => report_error(&e),
+                },
+                3 => match get_setting(&args[2]) {
+                    Ok(Some(value)) => println!("{}", value),
+                    Ok(None) => println!("{} is not set (using default)", args[2]),
+                    Err(e) => report_error(&e),
+                },
+                _ => {
+                    let result = if args[2] == "--unset" {
+                        unset_setting(&args[3])
+                    } else {
+                        set_setting(&args[2], &args[3])
+                    };
+                    match result {
+                        Ok(message) => println!("{}", message),
+                        Err(e) => report_error(&e),
+                    }
+                }
+            },
+            "update" => {
+                let mut update_args: Vec<String> = args[2..].to_vec();
+                let global = take_flag(&mut update_args, "--global");
+                let name = take_option(&mut update_args, "--name");
+                let email = take_option(&mut update_args, "--email");
+                match (global, update_args.as_slice()) {
+                    (true, [alias]) => {
+                        match update_global_alias(alias, name.as_deref(), email.as_deref()) {
+                            Ok(message) => println!("{}", message),
+                            Err(e) => report_error(&e),
+                        }
+                    }
+                    _ => usage(&[
+                        "Usage: git-pair update --global <alias> [--name \"Name\"] [--email <email>]",
+                    ]),
+                }
+            }
+            "rename" => {
+                if args.len() >= 5 && args[2] == "--global" {
+                    match rename_global_alias(&args[3], &args[4]) {
                         Ok(message) => println!("{}", message),
-                        Err(e) => eprintln!("Error: {}", e),
+                        Err(e) => report_error(&e),
                     }
                 } else {
-                    eprintln!("Usage: git-pair remove <name|email|alias>");
-                    eprintln!("Examples:");
-                    eprintln!("  git-pair remove \"John Doe\"");
-                    eprintln!("  git-pair remove john.doe@example.com");
-                    eprintln!("  git-pair remove alice");
+                    usage(&["Usage: git-pair rename --global <old-alias> <new-alias>"]);
                 }
             }
             "status" | "list" => {
-                if args.len() >= 3 && args[2] == "--global" {
+                let porcelain = args[2..].iter().any(|a| a == "--porcelain");
+                let global = args[2..].iter().any(|a| a == "--global");
+                let all = args[2..].iter().any(|a| a == "--all");
+                let json = JSON_OUTPUT.load(Ordering::Relaxed);
+                let mut audit_args: Vec<String> = args[2..].to_vec();
+                let audit = args[1] == "status" && take_flag(&mut audit_args, "--audit");
+                if audit {
+                    let limit = take_option(&mut audit_args, "--limit");
+                    let limit = match limit.map(|limit| limit.parse::<usize>().map_err(|_| limit)) {
+                        None => DEFAULT_AUDIT_LIMIT,
+                        Some(Ok(limit)) if limit > 0 => limit,
+                        Some(_) => {
+                            report(
+                                ErrorCode::InvalidValue,
+                                "--limit must be a positive number of commits",
+                            );
+                            return;
+                        }
+                    };
+                    match audit_recent_commits(limit) {
+                        Ok(commits) => print_unpaired_commits(&commits, limit),
+                        Err(e) => report_error(&e),
+                    }
+                } else if porcelain || json && (global || args.len() == 2 || args[1] == "status")
+                {
+                    let output = match (porcelain, global) {
+                        (true, true) => roster_porcelain_with(all),
+                        (true, false) => status_porcelain(),
+                        (false, true) => roster_json_with(all),
+                        (false, false) => status_json(None),
+                    };
+                    match output {
+                        Ok(output) => print!("{}", output),
+                        Err(e) => report_error(&e),
+                    }
+                } else if args.len() >= 3 && args[2] == "--repos" {
+                    // List registered repositories and their active pairings
+                    match list_repo_pairings() {
+                        Ok(repos) => {
+                            if repos.is_empty() {
+                                println!("No repositories registered with git-pair");
+                            }
+                            for (repo, branches) in repos {
+                                match branches {
+                                    Ok(branches) if branches.is_empty() => {
+                                        println!("{}: no active co-authors", repo.display())
+                                    }
+                                    Ok(branches) => {
+                                        println!("{}:", repo.display());
+                                        for branch in branches {
+                                            println!("  {}:", branch.branch);
+                                            for coauthor in branch.coauthors {
+                                                println!("    {}", coauthor);
+                                            }
+                                        }
+                                    }
+                                    Err(e) => println!("{}: {}", repo.display(), e),
+                                }
+                            }
+                        }
+                        Err(e) => report_error(&e),
+                    }
+                } else if args.len() >= 3 && args[2] == "--branches" {
+                    match list_branch_pairings() {
+                        Ok(pairings) => print_branch_pairings(&pairings),
+                        Err(e) => report_error(&e),
+                    }
+                } else if args.len() >= 3 && args[2] == "--repo" {
+                    // List the repository's committed roster
+                    match get_repo_roster() {
+                        Ok(roster) if roster.is_empty() => {
+                            println!("No entries in the repository roster");
+                            println!("Use 'git pair add --repo <alias> <name> <email>' to add entries");
+                        }
+                        Ok(roster) => {
+                            println!("Repository roster:");
+                            for (alias, name, email) in &roster {
+                                if has_placeholder_email(email) {
+                                    println!("  {} -> {} (no email yet)", alias, name);
+                                } else {
+                                    println!("  {} -> {} <{}>", alias, name, email);
+                                }
+                            }
+                        }
+                        Err(e) => report_error(&e),
+                    }
+                } else if args.len() >= 3 && args[2] == "--global" {
                     // List global roster
                     match get_global_roster() {
-                        Ok(roster) => {
-                            if roster.is_empty() {
+                        Ok(mut roster) => {
+                            let archived = get_archived_aliases().unwrap_or_default();
+                            let total = roster.len();
+                            if !all {
+                                roster.retain(|(alias, _, _)| !archived.contains(alias));
+                            }
+                            if roster.is_empty() && total == 0 {
                                 println!("No entries in global roster");
                                 println!("Use 'git pair add --global <alias> <name> <email>' to add entries");
                             } else {
                                 println!("Global roster:");
-                                for (alias, name, email) in roster {
-                                    println!("  {} -> {} <{}>", alias, name, email);
+                                for (alias, name, email) in &roster {
+                                    let mark = if archived.contains(alias) {
+                                        "  (archived)"
+                                    } else {
+                                        ""
+                                    };
+                                    if has_placeholder_email(email) {
+                                        println!("  {} -> {} (no email yet){}", alias, name, mark);
+                                    } else {
+                                        println!("  {} -> {} <{}>{}", alias, name, email, mark);
+                                    }
+                                }
+                                if roster.len() < total {
+                                    println!(
+                                        "{} archived entries hidden, use --all to show them",
+                                        total - roster.len()
+                                    );
                                 }
                             }
                         }
-                        Err(e) => eprintln!("Error: {}", e),
+                        Err(e) => report_error(&e),
                     }
                 } else {
                     // List current branch co-authors
@@ -98,62 +1789,2608 @@ fn main() {
                             if coauthors.is_empty() {
                                 println!("No co-authors configured for current branch");
                             } else {
-                                println!("Current co-authors:");
-                                for coauthor in coauthors {
+                                match get_max_coauthors() {
+                                    Ok(Some(max)) => println!(
+                                        "Current co-authors ({}/{} max):",
+                                        coauthors.len(),
+                                        max
+                                    ),
+                                    _ => println!("Current co-authors ({}):", coauthors.len()),
+                                }
+                                for coauthor in &coauthors {
+                                    if has_placeholder_email(coauthor) {
+                                        println!("  {}  (placeholder email)", coauthor);
+                                    } else {
+                                        println!("  {}", coauthor);
+                                    }
+                                }
+                                if uses_default_coauthors().unwrap_or(false) {
+                                    println!("  (the repository default; 'git-pair init' gives the branch its own)");
+                                }
+                                if coauthors.iter().any(|c| has_placeholder_email(c)) {
+                                    println!();
+                                    println!("Note: placeholder emails don't link commits to anyone. Set the real email with");
+                                    println!("'git-pair add --global <alias> <name> <email> --force', then remove and re-add them.");
+                                }
+                            }
+                            let others = get_other_trailers().unwrap_or_default();
+                            if !others.is_empty() {
+                                println!();
+                                println!("Other trailers ({}):", others.len());
+                                for trailer in &others {
+                                    println!("  {}", trailer);
+                                }
+                            }
+                            let expiring = get_expiring_coauthors().unwrap_or_default();
+                            if !expiring.is_empty() {
+                                println!();
+                                println!("Expiring ({}):", expiring.len());
+                                for coauthor in &expiring {
+                                    println!("  {}", coauthor);
+                                }
+                                println!("'git-pair renew' extends the session");
+                            }
+                            let once = get_once_coauthors().unwrap_or_default();
+                            if !once.is_empty() {
+                                println!();
+                                println!("Next commit only ({}):", once.len());
+                                for coauthor in &once {
                                     println!("  {}", coauthor);
                                 }
                             }
+                            if let Ok(Some(driver)) = get_driver() {
+                                println!();
+                                println!("Driver: {} <{}>", driver.name, driver.email);
+                            }
+                            if is_paused().unwrap_or(false) {
+                                println!();
+                                println!(
+                                    "Paused: co-authors will not be added to commits until 'git-pair resume'"
+                                );
+                            }
+                            if let Ok(Some(until)) = snoozed_until() {
+                                println!();
+                                println!(
+                                    "Snoozed: co-authors will not be added to commits until {}",
+                                    until
+                                );
+                            }
+                            if is_disabled_by_env() {
+                                println!();
+                                println!(
+                                    "Note: GIT_PAIR_DISABLE=1 is set, co-authors will not be added to commits"
+                                );
+                            }
+                            if let Ok(Some(has_coauthors)) = last_commit_has_coauthors() {
+                                println!();
+                                println!(
+                                    "Last commit included co-authors: {}",
+                                    if has_coauthors { "yes" } else { "no" }
+                                );
+                                if !has_coauthors && last_commit_missed_coauthors().unwrap_or(false) {
+                                    println!("If you paired on it, run 'git-pair check-config' or 'git-pair repair-hook'.");
+                                }
+                            }
+                            if is_hook_free().unwrap_or(false) {
+                                println!();
+                                println!("Hook-free mode (hook-engine none): git-pair doesn't install hooks here.");
+                                println!("Add co-authors with: git commit -m \"msg\" -m \"$(git pair message)\"");
+                            }
+                            if let Ok(hook_status) = check_hook() {
+                                if hook_status.needs_repair() {
+                                    println!();
+                                    println!(
+                                        "Warning: git-pair hook is {}",
+                                        hook_status.description()
+                                    );
+                                    println!("Run 'git-pair repair-hook' to reinstall it.");
+                                }
+                            }
                         }
-                        Err(e) => eprintln!("Error: {}", e),
+                        Err(e) => report_error(&e),
                     }
                 }
             }
-            _ => {
-                eprintln!("Unknown command: {}", args[1]);
-                eprintln!("Use 'git-pair --help' for usage information.");
+            "upgrade-hooks" => {
+                let all = args[2..].iter().any(|a| a == "--all");
+                match upgrade_hooks(all) {
+                    Ok(results) => {
+                        if results.is_empty() {
+                            println!("No repositories registered with git-pair");
+                        }
+                        for (repo, result) in results {
+                            match result {
+                                Ok(HookUpgrade::Upgraded) => {
+                                    println!("{}: upgraded", repo.display())
+                                }
+                                Ok(HookUpgrade::UpToDate) => {
+                                    println!("{}: up to date", repo.display())
+                                }
+                                Ok(HookUpgrade::Newer) => println!(
+                                    "{}: skipped (installed by a newer git-pair)",
+                                    repo.display()
+                                ),
+                                Ok(HookUpgrade::NotInstalled) => {
+                                    println!("{}: no git-pair hook installed", repo.display())
+                                }
+                                Err(e) => println!("{}: error: {}", repo.display(), e),
+                            }
+                        }
+                    }
+                    Err(e) => report_error(&e),
+                }
             }
-        }
-    } else {
-        print_help();
-    }
-}
-
-fn print_help() {
-    let help_text = format!(
-        r#"git-pair {}
-A git extension for pair programming with per-branch co-author management
-
-USAGE:
-    git-pair <COMMAND>
-
-COMMANDS:
-    init                                    Initialize git-pair for current branch
-    add <name> <surname> <email>            Add a co-author to current branch
-    add <alias>                             Add co-author from global roster
-    add --global <alias> <name> <email>     Add co-author to global roster
-    remove <name|email|alias>               Remove a specific co-author from current branch
-    clear                                   Remove all co-authors from current branch
-    status                                  Show current branch co-authors
-    list --global                           Show global roster
-    help, --help, -h                        Show this help message
-    --version, -V                           Show version information
-
-ENVIRONMENT VARIABLES:
-    GIT_PAIR_ROSTER_FILE                    Override global roster file location
-
-EXAMPLES:
-    git-pair init
-    git-pair add John Doe john.doe@company.com
-    git-pair add --global alice "Alice Johnson" alice@company.com
-    git-pair add alice
-    git-pair remove "John Doe"
-    git-pair remove john.doe@company.com
-    git-pair remove alice
-    git-pair status
-    git-pair list --global
-"#,
-        env!("CARGO_PKG_VERSION")
-    );
-
-    print!("{}", help_text);
+            "install" => {
+                let mut install_args = args[2..].to_vec();
+                let mode = take_option(&mut install_args, "--mode").or_else(|| {
+                    install_args
+                        .iter()
+                        .find_map(|a| a.strip_prefix("--mode=").map(str::to_string))
+                });
+                match mode {
+                    Some(mode) => match install_mode(&mode) {
+                        Ok(message) => println!("{}", message),
+                        Err(e) => report_error(&e),
+                    },
+                    None => usage(&["Usage: git-pair install --mode <hook|template>"]),
+                }
+            }
+            "install-hooks" => {
+                let mut install_args = args[2..].to_vec();
+                let apply = install_args.iter().any(|a| a == "--apply");
+                install_args.retain(|a| a != "--apply");
+                match take_option(&mut install_args, "--manager") {
+                    Some(manager) => match HookManager::parse(&manager)
+                        .and_then(|manager| install_hooks(manager, apply))
+                    {
+                        Ok(output) => println!("{}", output),
+                        Err(e) => report_error(&e),
+                    },
+                    None => usage(&[
+                        "Usage: git-pair install-hooks --manager <plain|husky|lefthook|pre-commit|overcommit> [--apply]",
+                    ]),
+                }
+            }
+            "hook-impl" => {
+                if args.len() >= 3 {
+                    if let Err(e) = run_hook_impl(&args[2], &args[3..]) {
+                        report_error(&e);
+                    }
+                } else {
+                    usage(&["Usage: git-pair hook-impl prepare-commit-msg <msgfile> [source]"]);
+                }
+            }
+            // Hidden: called by the shim hook installed with `hook-engine binary`
+            "hook" => {
+                if args.len() >= 5 && args[2] == "run" && args[3] == "prepare-commit-msg" {
+                    let source = args.get(5).map(String::as_str);
+                    if let Err(e) = run_prepare_commit_msg_hook(Path::new(&args[4]), source) {
+                        report_error(&e);
+                    }
+                } else if args.len() >= 4 && args[2] == "run" && args[3] == "post-checkout" {
+                    let branch_checkout = args.get(6).map(String::as_str) == Some("1");
+                    match run_post_checkout_hook(branch_checkout) {
+                        Ok(Some(line)) => println!("{}", line),
+                        Ok(None) => {}
+                        Err(e) => report_error(&e),
+                    }
+                } else if args.len() >= 4 && args[2] == "run" && args[3] == "post-commit" {
+                    if let Err(e) = run_post_commit_hook() {
+                        report_error(&e);
+                    }
+                } else if args.len() >= 5 && args[2] == "run" && args[3] == "commit-msg" {
+                    match run_commit_msg_hook(Path::new(&args[4])) {
+                        Ok(None) => {}
+                        // The one hook outcome that stops a commit
+                        Ok(Some(reason)) => {
+                            eprintln!("{}", reason);
+                            process::exit(1);
+                        }
+                        Err(e) => report_error(&e),
+                    }
+                } else if args.len() >= 3 && args[2] == "test" {
+                    let mut test_args: Vec<String> = args[3..].to_vec();
+                    let message_file = take_option(&mut test_args, "--message-file");
+                    let source = take_option(&mut test_args, "--source");
+                    match simulate_hook(message_file.as_deref().map(Path::new), source.as_deref())
+                    {
+                        Ok(test) => print_hook_test(&test),
+                        Err(e) => report_error(&e),
+                    }
+                } else {
+                    usage(&[
+                        "Usage: git-pair hook test [--message-file <file>] [--source <source>]",
+                        "       git-pair hook run prepare-commit-msg <msgfile> [source]",
+                        "       git-pair hook run post-checkout <prev> <new> <flag>",
+                        "       git-pair hook run commit-msg <msgfile>",
+                        "       git-pair hook run post-commit",
+                    ]);
+                }
+            }
+            "which" => {
+                if args.len() >= 3 {
+                    match which_alias(&args[2]) {
+                        Ok(Some(resolution)) => {
+                            println!(
+                                "{} -> {} <{}>",
+                                resolution.alias, resolution.name, resolution.email
+                            );
+                            if resolution.line > 0 {
+                                println!(
+                                    "  source:  {} (line {})",
+                                    resolution.source.display(),
+                                    resolution.line
+                                );
+                            } else {
+                                println!("  source:  {}", resolution.source.display());
+                            }
+                            println!("  matched: {}", resolution.matched.description());
+                            for source in &resolution.overrides {
+                                println!("  overrides: {}", source.display());
+                            }
+                            if let Some((_, spec)) = get_pins()
+                                .unwrap_or_default()
+                                .into_iter()
+                                .find(|(alias, _)| *alias == resolution.alias)
+                            {
+                                println!("  pinned:  {}", spec);
+                            }
+                        }
+                        Ok(None) => {
+                            report_error(&GitPairError::AliasNotFound {
+                                alias: args[2].clone(),
+                            })
+                        }
+                        Err(e) => report_error(&e),
+                    }
+                } else {
+                    usage(&["Usage: git-pair which <alias>"]);
+                }
+            }
+            "pin" => {
+                let mut pin_args: Vec<String> = args[2..].to_vec();
+                let clear = take_flag(&mut pin_args, "--clear");
+                match (clear, pin_args.as_slice()) {
+                    (false, []) => match get_pins() {
+                        Ok(pins) if pins.is_empty() => println!("No aliases pinned in this repository"),
+                        Ok(pins) => {
+                            for (alias, spec) in pins {
+                                println!("{}={}", alias, spec);
+                            }
+                        }
+                        Err(e) => report_error(&e),
+                    },
+                    (true, [alias]) => match unpin_alias(alias) {
+                        Ok(true) => println!("Unpinned '{}'", alias),
+                        Ok(false) => println!("'{}' isn't pinned", alias),
+                        Err(e) => report_error(&e),
+                    },
+                    (false, [pin]) if pin.contains('=') => {
+                        let (alias, spec) = pin.split_once('=').unwrap_or_default();
+                        match pin_alias(alias, spec) {
+                            Ok(()) => println!("Pinned '{}' to {} in this repository", alias, spec),
+                            Err(e) => report_error(&e),
+                        }
+                    }
+                    _ => usage(&[
+                        "Usage: git-pair pin [<alias>=<provider>|--clear <alias>]",
+                        "Examples:",
+                        "  git-pair pin alice=repo",
+                        "  git-pair pin --clear alice",
+                    ]),
+                }
+            }
+            "stats" => {
+                let mut stats_args: Vec<String> = args[2..].to_vec();
+                let gap = take_option(&mut stats_args, "--gap");
+                let time = take_flag(&mut stats_args, "--time") || gap.is_some();
+                let heatmap = take_flag(&mut stats_args, "--heatmap");
+                let fun = take_flag(&mut stats_args, "--fun");
+                let compare = take_flag(&mut stats_args, "--compare");
+                let report_kind = take_option(&mut stats_args, "--report");
+                let interval = take_option(&mut stats_args, "--interval");
+                let period = take_option(&mut stats_args, "--period");
+                let csv = take_flag(&mut stats_args, "--csv");
+                let json = JSON_OUTPUT.load(Ordering::Relaxed);
+                let since_a = take_option(&mut stats_args, "--since-a");
+                let until_a = take_option(&mut stats_args, "--until-a");
+                let since_b = take_option(&mut stats_args, "--since-b");
+                let until_b = take_option(&mut stats_args, "--until-b");
+                let filter = StatsFilter {
+                    author: take_option(&mut stats_args, "--author"),
+                    coauthor: take_option(&mut stats_args, "--coauthor"),
+                    branch: take_option(&mut stats_args, "--branch"),
+                    path: take_option(&mut stats_args, "--path"),
+                    since: take_option(&mut stats_args, "--since"),
+                    until: take_option(&mut stats_args, "--until"),
+                };
+                if (csv || json) && (time || heatmap || fun || compare || report_kind.is_some()) {
+                    usage(&["--csv and --json can't be used with --time, --heatmap, --fun, --compare or --report"]);
+                    return;
+                }
+                if let Some(kind) = report_kind {
+                    if kind != "velocity" {
+                        report(
+                            ErrorCode::InvalidValue,
+                            &format!("Unknown report '{}'. Use 'velocity'.", kind),
+                        );
+                        return;
+                    }
+                    match parse_stats_period(interval.as_deref().unwrap_or("sprint"))
+                        .and_then(|interval| velocity_report(&filter, interval))
+                    {
+                        Ok(intervals) => print_velocity_report(&intervals),
+                        Err(e) => report_error(&e),
+                    }
+                    return;
+                }
+                if interval.is_some() {
+                    usage(&["Usage: git-pair stats --report velocity [--interval <week|sprint|month|duration>]"]);
+                    return;
+                }
+                if compare {
+                    let windows = match (period, since_a, since_b) {
+                        (Some(period), None, None) => parse_stats_period(&period).map(period_windows),
+                        (None, Some(since_a), Some(since_b)) => Ok((
+                            StatsWindow {
+                                label: format!(
+                                    "{} to {}",
+                                    since_a,
+                                    until_a.as_deref().unwrap_or(&since_b)
+                                ),
+                                until: Some(until_a.unwrap_or_else(|| since_b.clone())),
+                                since: Some(since_a),
+                            },
+                            StatsWindow {
+                                label: format!(
+                                    "{} to {}",
+                                    since_b,
+                                    until_b.as_deref().unwrap_or("now")
+                                ),
+                                since: Some(since_b),
+                                until: until_b,
+                            },
+                        )),
+                        _ => {
+                            usage(&[
+                                "Usage: git-pair stats --compare --period <week|sprint|month|duration>",
+                                "       git-pair stats --compare --since-a <when> [--until-a <when>] --since-b <when> [--until-b <when>]",
+                            ]);
+                            return;
+                        }
+                    };
+                    if filter.since.is_some() || filter.until.is_some() {
+                        usage(&["--since and --until can't be used with --compare; use --since-a and --since-b"]);
+                        return;
+                    }
+                    match windows.and_then(|(a, b)| {
+                        Ok((compare_pairing_stats(&filter, &a, &b)?, a, b))
+                    }) {
+                        Ok((comparison, a, b)) => print_stats_comparison(&a, &b, &comparison),
+                        Err(e) => report_error(&e),
+                    }
+                    return;
+                }
+                let gap = match gap.map(|gap| gap.parse::<u64>().map_err(|_| gap)).transpose() {
+                    Ok(Some(0)) | Err(_) => {
+                        report(
+                            ErrorCode::InvalidValue,
+                            "--gap must be a positive number of minutes",
+                        );
+                        return;
+                    }
+                    Ok(gap) => gap,
+                };
+                match get_pairing_stats(&filter) {
+                    Ok(stats) if json => print!("{}", stats_json(&stats)),
+                    Ok(stats) if csv => print!("{}", stats_csv(&stats)),
+                    Ok(stats) => print_pairing_stats(&stats, &filter),
+                    Err(e) => {
+                        report_error(&e);
+                        return;
+                    }
+                }
+                if time {
+                    match get_pairing_time(gap, &filter) {
+                        Ok(times) => print_pairing_time(&times),
+                        Err(e) => report_error(&e),
+                    }
+                }
+                if heatmap {
+                    match pairing_heatmap(&filter) {
+                        Ok(chart) => print_heatmap(&chart),
+                        Err(e) => report_error(&e),
+                    }
+                }
+                if fun {
+                    match get_pairing_streaks(&filter) {
+                        Ok(streaks) => print_pairing_streaks(&streaks),
+                        Err(e) => report_error(&e),
+                    }
+                }
+            }
+            "verify" => {
+                let mut verify_args: Vec<String> = args[2..].to_vec();
+                let branch = take_option(&mut verify_args, "--branch");
+                if verify_args.len() > 1 {
+                    usage(&["Usage: git-pair verify [<range>] [--branch <name>]"]);
+                    return;
+                }
+                match verify_policy(verify_args.first().map(String::as_str), branch.as_deref()) {
+                    Ok(report) => print_policy_report(&report),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "check" => match &args[2..] {
+                [range] => match check_pairing(range) {
+                    Ok(check) => print_pairing_check(&check),
+                    Err(e) => report_error(&e),
+                },
+                _ => usage(&[
+                    "Usage: git-pair check <range>",
+                    "Lists commits in the range without a co-author from the roster, and fails if there are any",
+                    "Example: git-pair check origin/main..HEAD",
+                ]),
+            },
+            "amend" => {
+                let mut amend_args: Vec<String> = args[2..].to_vec();
+                let dry_run = take_flag(&mut amend_args, "--dry-run");
+                let force = take_flag(&mut amend_args, "--force");
+                let since = take_option(&mut amend_args, "--since");
+                let last = match take_option(&mut amend_args, "--last") {
+                    Some(last) => match last.parse::<usize>() {
+                        Ok(last) if last > 0 => Some(last),
+                        _ => {
+                            report(
+                                ErrorCode::InvalidValue,
+                                "--last must be a positive number of commits",
+                            );
+                            return;
+                        }
+                    },
+                    None => None,
+                };
+                if !amend_args.is_empty() {
+                    usage(&[
+                        "Usage: git-pair amend [--last <n> | --since <ref>] [--force] [--dry-run]",
+                    ]);
+                    return;
+                }
+                let options = AmendOptions {
+                    last,
+                    since,
+                    force,
+                    dry_run,
+                };
+                match amend_commits(&options) {
+                    Ok(amend) => print_amend(&amend, dry_run),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "adopt" => {
+                let mut adopt_args: Vec<String> = args[2..].to_vec();
+                let roster = take_flag(&mut adopt_args, "--roster");
+                let dry_run = take_flag(&mut adopt_args, "--dry-run");
+                let limit = match take_option(&mut adopt_args, "--last") {
+                    Some(limit) => match limit.parse::<usize>() {
+                        Ok(limit) if limit > 0 => limit,
+                        _ => {
+                            report(
+                                ErrorCode::InvalidValue,
+                                "--last must be a positive number of commits",
+                            );
+                            return;
+                        }
+                    },
+                    None => DEFAULT_ADOPT_LIMIT,
+                };
+                if !adopt_args.is_empty() {
+                    usage(&["Usage: git-pair adopt [--last <n>] [--roster] [--dry-run]"]);
+                    return;
+                }
+                match adopt_coauthors(limit, roster, dry_run) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "squash-msg" => {
+                let mut squash_args: Vec<String> = args[2..].to_vec();
+                let dry_run = take_flag(&mut squash_args, "--dry-run");
+                if squash_args.len() > 1 {
+                    usage(&["Usage: git-pair squash-msg [<branch>] [--dry-run]"]);
+                    return;
+                }
+                match squash_message_coauthors(squash_args.first().map(String::as_str), dry_run) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "graph" => {
+                let mut graph_args: Vec<String> = args[2..].to_vec();
+                let format = take_option(&mut graph_args, "--format")
+                    .map(|format| GraphFormat::parse(&format))
+                    .transpose();
+                match format.and_then(|format| pairing_graph(format.unwrap_or_default())) {
+                    Ok(graph) => print!("{}", graph),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "blame" => match args.get(2) {
+                Some(file) => match blame(Path::new(file)) {
+                    Ok(lines) => print_blame(&lines),
+                    Err(e) => report_error(&e),
+                },
+                None => usage(&["Usage: git-pair blame <file>"]),
+            },
+            "standup" => {
+                let mut standup_args: Vec<String> = args[2..].to_vec();
+                let since =
+                    take_option(&mut standup_args, "--since").unwrap_or("yesterday".to_string());
+                match get_standup(&since) {
+                    Ok(groups) => print_standup(&since, &groups),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "mob" => {
+                let mut mob_args: Vec<String> = args[2..].to_vec();
+                let message = take_option(&mut mob_args, "-m")
+                    .or_else(|| take_option(&mut mob_args, "--message"));
+                let minutes = match take_option(&mut mob_args, "--minutes") {
+                    Some(minutes) => match minutes.parse::<u64>().ok().filter(|m| *m > 0) {
+                        Some(minutes) => Some(minutes),
+                        None => {
+                            report(
+                                ErrorCode::InvalidValue,
+                                "--minutes must be a positive number of minutes",
+                            );
+                            return;
+                        }
+                    },
+                    None => None,
+                };
+                let result = match mob_args.first().map(String::as_str) {
+                    Some("start") => Some(mob_start_with(&MobStartOptions {
+                        aliases: mob_args[1..].to_vec(),
+                        minutes,
+                    })),
+                    Some("next") => Some(mob_next()),
+                    Some("status") => Some(mob_status()),
+                    Some("done") => Some(mob_done(message.as_deref())),
+                    _ => None,
+                };
+                match result {
+                    Some(Ok(message)) => println!("{}", message),
+                    Some(Err(e)) => report_error(&e),
+                    None => usage(&[
+                        "Usage: git-pair mob start [--minutes <n>] [<alias>...]|next|status|done [-m <message>]",
+                    ]),
+                }
+            }
+            "rotate" => {
+                let mut rotate_args: Vec<String> = args[2..].to_vec();
+                let schedule = take_option(&mut rotate_args, "--schedule");
+                let date = take_option(&mut rotate_args, "--date");
+                match rotate(schedule.as_deref().map(Path::new), date.as_deref()) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "recent" => {
+                let mut recent_args: Vec<String> = args[2..].to_vec();
+                match take_option(&mut recent_args, "--use") {
+                    Some(number) => match number.parse::<usize>() {
+                        Ok(number) => match use_recent_pairing(number) {
+                            Ok(message) => println!("{}", message),
+                            Err(e) => report_error(&e),
+                        },
+                        Err(_) => report(
+                            ErrorCode::InvalidValue,
+                            &format!("'{}' is not a recent pairing number", number),
+                        ),
+                    },
+                    None => match get_recent_pairings() {
+                        Ok(pairings) => print_recent_pairings(&pairings),
+                        Err(e) => report_error(&e),
+                    },
+                }
+            }
+            "save-set" => {
+                let force = args[2..].iter().any(|a| a == "--force");
+                match args[2..].iter().find(|a| !a.starts_with("--")) {
+                    Some(name) => match save_preset(name, force) {
+                        Ok(message) => println!("{}", message),
+                        Err(e) => report_error(&e),
+                    },
+                    None => usage(&["Usage: git-pair save-set <name> [--force]"]),
+                }
+            }
+            "use" => match args.get(2) {
+                Some(name) => match use_preset(name) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                },
+                None => match get_presets() {
+                    Ok(presets) if presets.is_empty() => println!(
+                        "No presets saved. Save this branch's co-authors with 'git-pair save-set <name>'."
+                    ),
+                    Ok(presets) => {
+                        println!("Presets:");
+                        for preset in &presets {
+                            let names: Vec<&str> =
+                                preset.coauthors.iter().map(|p| p.name.as_str()).collect();
+                            println!("  {} -> {}", preset.name, names.join(", "));
+                        }
+                    }
+                    Err(e) => report_error(&e),
+                },
+            },
+            "group" => {
+                let mut group_args: Vec<String> = args[2..].to_vec();
+                let force = take_flag(&mut group_args, "--force");
+                let dry_run = take_flag(&mut group_args, "--dry-run");
+                let style = take_option(&mut group_args, "--style")
+                    .map(|style| TrailerStyle::parse(&style))
+                    .transpose();
+                match group_args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+                    ["add", name, ref aliases @ ..] if !aliases.is_empty() => {
+                        match add_group(name, aliases, force) {
+                            Ok(message) => println!("{}", message),
+                            Err(e) => report_error(&e),
+                        }
+                    }
+                    ["remove", name] => match remove_group(name) {
+                        Ok(message) => println!("{}", message),
+                        Err(e) => report_error(&e),
+                    },
+                    ["list"] | [] => match get_groups() {
+                        Ok(groups) if groups.is_empty() => println!(
+                            "No groups saved. Define one with 'git-pair group add <name> <alias>...'."
+                        ),
+                        Ok(groups) => {
+                            println!("Groups:");
+                            for group in &groups {
+                                println!("  @{} -> {}", group.name, group.aliases.join(", "));
+                            }
+                        }
+                        Err(e) => report_error(&e),
+                    },
+                    ["apply", name] => {
+                        let result = style.and_then(|style| {
+                            let options = AddOptions {
+                                style: style.unwrap_or_default(),
+                                dry_run,
+                                ..AddOptions::default()
+                            };
+                            apply_group(name, &options)
+                        });
+                        match result {
+                            Ok(message) => println!("{}", message),
+                            Err(e) => report_error(&e),
+                        }
+                    }
+                    _ => usage(&[
+                        "Usage: git-pair group add <name> <alias>... [--force]",
+                        "       git-pair group remove <name>",
+                        "       git-pair group list",
+                        "       git-pair group apply <name> [--style <style>] [--dry-run]",
+                        "A group's members can also be added with 'git-pair add @<name>'",
+                    ]),
+                }
+            }
+            "announce" => {
+                let mut announce_args: Vec<String> = args[2..].to_vec();
+                let format = take_option(&mut announce_args, "--format")
+                    .map(|format| AnnounceFormat::parse(&format))
+                    .transpose();
+                match format.and_then(|format| announce(format.unwrap_or_default())) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "share" => {
+                let mut share_args: Vec<String> = args[2..].to_vec();
+                let qr = take_flag(&mut share_args, "--qr");
+                if !share_args.is_empty() {
+                    usage(&["Usage: git-pair share [--qr]"]);
+                    return;
+                }
+                match share_snippet().and_then(|snippet| {
+                    if qr {
+                        Ok(format!("{}{}", render_qr_code(&snippet)?, snippet))
+                    } else {
+                        Ok(snippet)
+                    }
+                }) {
+                    Ok(output) => println!("{}", output),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "apply" => {
+                let mut apply_args: Vec<String> = args[2..].to_vec();
+                let dry_run = take_flag(&mut apply_args, "--dry-run");
+                if apply_args.len() != 1 {
+                    usage(&[
+                        "Usage: git-pair apply [--dry-run] <snippet|file|url|->",
+                        "Use '-' to read the snippet from stdin",
+                    ]);
+                    return;
+                }
+                let text = if apply_args[0] == "-" {
+                    let mut input = String::new();
+                    io::stdin()
+                        .read_to_string(&mut input)
+                        .map(|_| input)
+                        .map_err(|e| GitPairError::io("reading the snippet from stdin", e))
+                } else {
+                    read_snippet_source(&apply_args[0])
+                };
+                // A snippet can come from anyone, so what it would change is shown and
+                // confirmed first
+                let result = text.and_then(|text| {
+                    let preview = apply_snippet(&text, true)?;
+                    if dry_run {
+                        println!("{}", preview);
+                        return Ok(());
+                    }
+                    println!("{}", preview.replacen("Dry run, nothing was written.\n", "", 1));
+                    if confirm("Apply this pairing?")? {
+                        println!("{}", apply_snippet(&text, false)?);
+                    }
+                    Ok(())
+                });
+                if let Err(e) = result {
+                    report_error(&e);
+                }
+            }
+            "events" => {
+                let mut events_args: Vec<String> = args[2..].to_vec();
+                let follow = take_flag(&mut events_args, "--follow") | take_flag(&mut events_args, "-f");
+                let new = take_flag(&mut events_args, "--new");
+                if !events_args.is_empty() || (new && !follow) {
+                    usage(&["Usage: git-pair events [--follow [--new]]"]);
+                    return;
+                }
+                let mut offset = if new {
+                    match events_end() {
+                        Ok(end) => end,
+                        Err(e) => {
+                            report_error(&e);
+                            return;
+                        }
+                    }
+                } else {
+                    0
+                };
+                loop {
+                    match read_events(offset) {
+                        Ok((events, next)) => {
+                            print!("{}", events);
+                            let _ = io::stdout().flush();
+                            offset = next;
+                        }
+                        Err(e) => {
+                            report_error(&e);
+                            return;
+                        }
+                    }
+                    if !follow {
+                        break;
+                    }
+                    std::thread::sleep(EVENTS_POLL_INTERVAL);
+                }
+            }
+            "pr-body" => {
+                let mut pr_args: Vec<String> = args[2..].to_vec();
+                let base = take_option(&mut pr_args, "--base");
+                let template = take_option(&mut pr_args, "--template");
+                match get_pr_body(base.as_deref(), template.as_deref().map(Path::new)) {
+                    Ok(body) => print!("{}", body),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "preview" => {
+                let mut preview_args: Vec<String> = args[2..].to_vec();
+                let message = take_option(&mut preview_args, "-m")
+                    .or_else(|| take_option(&mut preview_args, "--message"));
+                match preview_commit_message(message.as_deref()) {
+                    Ok(preview) => print!("{}", preview),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "edit" => match edit_branch_config() {
+                Ok(message) => println!("{}", message),
+                Err(e) => report_error(&e),
+            },
+            "diff" => match args.len() {
+                2..=4 => match diff_branch_coauthors(
+                    args.get(2).map(|s| s.as_str()),
+                    args.get(3).map(|s| s.as_str()),
+                ) {
+                    Ok(diff) => print_coauthor_diff(&diff),
+                    Err(e) => report_error(&e),
+                },
+                _ => usage(&[
+                    "Usage: git-pair diff [<branch-a> [<branch-b>]]",
+                    "Compares branch-a (default main) with branch-b (default the current branch)",
+                    "Examples:",
+                    "  git-pair diff",
+                    "  git-pair diff feature/login feature/signup",
+                ]),
+            },
+            "info" => {
+                if args.len() >= 3 {
+                    match alias_info(&args[2]) {
+                        Ok(Some(info)) => print_alias_info(&info),
+                        Ok(None) => {
+                            report_error(&GitPairError::AliasNotFound {
+                                alias: args[2].clone(),
+                            })
+                        }
+                        Err(e) => report_error(&e),
+                    }
+                } else {
+                    usage(&["Usage: git-pair info <alias>"]);
+                }
+            }
+            "setup-alias" => {
+                let mut alias_args: Vec<String> = args[2..].to_vec();
+                let remove = take_flag(&mut alias_args, "--remove");
+                let force = take_flag(&mut alias_args, "--force");
+                match setup_git_aliases(remove, force) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                }
+            }
+            "env" => match environment_report() {
+                Ok(report) => {
+                    let width = report.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+                    for (label, value) in report {
+                        println!("{:<width$}  {}", format!("{}:", label), value, width = width + 1);
+                    }
+                }
+                Err(e) => report_error(&e),
+            },
+            "cache" => match args.get(2).map(String::as_str) {
+                Some("clear") => match clear_cache() {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                },
+                _ => usage(&["Usage: git-pair cache clear"]),
+            },
+            "auth" => match (args.get(2).map(String::as_str), args.get(3)) {
+                (Some("login"), Some(provider)) => {
+                    let result = AuthProvider::parse(provider).and_then(|provider| {
+                        let token = prompt_secret(&format!("{} token", provider.as_str()))?;
+                        auth_login(provider, &token)
+                    });
+                    match result {
+                        Ok(message) => println!("{}", message),
+                        Err(e) => report_error(&e),
+                    }
+                }
+                (Some("logout"), Some(provider)) => {
+                    match AuthProvider::parse(provider).and_then(auth_logout) {
+                        Ok(message) => println!("{}", message),
+                        Err(e) => report_error(&e),
+                    }
+                }
+                (Some("status"), None) => {
+                    for (provider, source) in auth_status() {
+                        let source = match source {
+                            Some(TokenSource::Environment) => {
+                                format!("from {}", provider.env_var())
+                            }
+                            Some(TokenSource::Keychain) => "from the keychain".to_string(),
+                            None => "not set".to_string(),
+                        };
+                        println!("{:<10} {}", provider.as_str(), source);
+                    }
+                }
+                _ => usage(&[
+                    "Usage: git-pair auth login|logout <github|gitlab|directory> | auth status",
+                ]),
+            },
+            "check-config" => {
+                let fix = args[2..].iter().any(|a| a == "--fix");
+                let dry_run = args[2..].iter().any(|a| a == "--dry-run");
+                match check_config(fix && !dry_run) {
+                    Ok(problems) => {
+                        if problems.is_empty() {
+                            println!("No problems found");
+                        }
+                        for problem in &problems {
+                            let location = match problem.line {
+                                Some(line) => format!("{}:{}", problem.file.display(), line),
+                                None => problem.file.display().to_string(),
+                            };
+                            let status = match (fix, problem.fixable) {
+                                (true, true) if dry_run => " (would fix)",
+                                (true, true) => " (fixed)",
+                                (false, true) => " (fixable with --fix)",
+                                _ => "",
+                            };
+                            println!("{}: {}{}", location, problem.message, status);
+                        }
+                        let changes = planned_fixes(&problems);
+                        if fix && dry_run && !changes.is_empty() {
+                            println!("Dry run, nothing was written. Would change:");
+                            for change in changes {
+                                println!("  {}", change);
+                            }
+                        }
+                    }
+                    Err(e) => report_error(&e),
+                }
+            }
+            "prune" => {
+                let dry_run = args[2..].iter().any(|a| a == "--dry-run");
+                // Deleting configs loses their co-authors, so they're shown and confirmed
+                // first, like roster dedupe
+                let result = plan_branch_prune().and_then(|plan| {
+                    if plan.branches.is_empty() {
+                        println!("Every branch config belongs to an existing branch");
+                        return Ok(());
+                    }
+                    println!("Would delete the configs of deleted branches:");
+                    for branch in &plan.branches {
+                        println!("  {}", branch);
+                    }
+                    if dry_run || !confirm("Delete these configs?")? {
+                        return Ok(());
+                    }
+                    apply_branch_prune(&plan)?;
+                    println!("Deleted {} branch config(s)", plan.branches.len());
+                    Ok(())
+                });
+                if let Err(e) = result {
+                    report_error(&e);
+                }
+            }
+            "move" => match &args[2..] {
+                [old_branch, new_branch] => match move_branch_config(old_branch, new_branch) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                },
+                _ => usage(&[
+                    "Usage: git-pair move <old-branch> <new-branch>",
+                    "Moves a branch's git-pair config to a branch renamed without 'git branch -m'",
+                ]),
+            },
+            "completions" => match &args[2..] {
+                [flag] if flag == "--aliases" => {
+                    // Run on every Tab: never fail, and leave archived people out
+                    let archived = get_archived_aliases().unwrap_or_default();
+                    for (alias, _, _) in get_roster().unwrap_or_default() {
+                        if !archived.contains(&alias) {
+                            println!("{}", alias);
+                        }
+                    }
+                }
+                [shell] => match completion_script(shell, COMMANDS) {
+                    Ok(script) => print!("{}", script),
+                    Err(e) => report_error(&e),
+                },
+                _ => usage(&[
+                    "Usage: git-pair completions <bash|zsh|fish|powershell>",
+                    "Prints a completion script, e.g. for bash:",
+                    "  source <(git-pair completions bash)",
+                ]),
+            },
+            "doctor" => match args[2..] {
+                [] => print_doctor(doctor(false), false),
+                [ref flag] if flag == "--fix" => print_doctor(doctor(true), true),
+                _ => usage(&["Usage: git-pair doctor [--fix]"]),
+            },
+            "repair-hook" => match repair_hook() {
+                Ok(message) => println!("{}", message),
+                Err(e) => report_error(&e),
+            },
+            "clean-hooks" => {
+                let dry_run = args[2..].iter().any(|a| a == "--dry-run");
+                match clean_hooks(dry_run) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => report_error(&e),
+                }
+            }
+            _ => {
+                usage(&[
+                    &format!("Unknown command: {}", args[1]),
+                    "Use 'git-pair --help' for usage information.",
+                ]);
+            }
+        }
+    } else {
+        print_help();
+    }
+}
+
+fn print_branch_pairings(pairings: &[BranchPairing]) {
+    if pairings.is_empty() {
+        println!("No branch has a git-pair config. Start one with 'git-pair init'.");
+        return;
+    }
+    println!("Branches with a git-pair config:");
+    let width = pairings.iter().map(|p| p.branch.len()).max().unwrap_or(0);
+    for pairing in pairings {
+        let marker = if pairing.current { '*' } else { ' ' };
+        let coauthors = if pairing.coauthors.is_empty() {
+            "no co-authors".to_string()
+        } else {
+            pairing.coauthors.join(", ")
+        };
+        let mut notes = Vec::new();
+        if pairing.paused {
+            notes.push("paused");
+        }
+        if !pairing.exists {
+            notes.push("branch deleted, 'git-pair prune' removes it");
+        }
+        let notes = if notes.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", notes.join("; "))
+        };
+        println!(
+            "{} {:<width$}  {}{}",
+            marker,
+            pairing.branch,
+            coauthors,
+            notes,
+            width = width
+        );
+    }
+}
+
+/// How often `events --follow` checks the log for new events
+const EVENTS_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Reports a failed command on stderr, with the exit status its error's code gives
+fn report_error(error: &GitPairError) {
+    report(error.code(), &friendly_message(error));
+}
+
+/// The error's message with a hint at what to run next, for the ones that have one
+fn friendly_message(error: &GitPairError) -> String {
+    let hint = match error.root() {
+        GitPairError::NotARepo => ". Please run 'git init' first.",
+        GitPairError::NotInitialized { .. } => ". Please run 'git-pair init' first.",
+        GitPairError::AliasNotFound { .. } => {
+            ". Use 'git pair list --global' or 'git pair list --repo' to see available aliases."
+        }
+        GitPairError::CoauthorNotFound { .. } => {
+            ". Use 'git-pair status' to see current co-authors."
+        }
+        _ => "",
+    };
+    format!("{}{}", error, hint)
+}
+
+fn report(code: ErrorCode, message: &str) {
+    if JSON_OUTPUT.load(Ordering::Relaxed) {
+        eprintln!(
+            "{{\"schema_version\":{},\"error\":{{\"code\":\"{}\",\"exit_code\":{},\"message\":{}}}}}",
+            JSON_SCHEMA_VERSION,
+            code.as_str(),
+            code.exit_code(),
+            json_string(message)
+        );
+    } else {
+        eprintln!("Error: {}", message);
+    }
+    EXIT_CODE.store(code.exit_code(), Ordering::Relaxed);
+}
+
+/// Prints what a command that changed the branch did, or with `--json` the branch's
+/// status with the message in it
+fn print_change(message: &str) {
+    if JSON_OUTPUT.load(Ordering::Relaxed) {
+        match status_json(Some(message)) {
+            Ok(json) => print!("{}", json),
+            Err(e) => report_error(&e),
+        }
+    } else {
+        println!("{}", message);
+    }
+}
+
+/// Prints a command's message, or with `--json` an object holding it
+fn print_message(message: &str) {
+    if JSON_OUTPUT.load(Ordering::Relaxed) {
+        println!(
+            "{{\"schema_version\":{},\"message\":{}}}",
+            JSON_SCHEMA_VERSION,
+            json_string(message)
+        );
+    } else {
+        println!("{}", message);
+    }
+}
+
+/// Reports a command line that couldn't be understood
+fn usage(lines: &[&str]) {
+    if JSON_OUTPUT.load(Ordering::Relaxed) {
+        report(ErrorCode::Usage, &lines.join("\n"));
+    } else {
+        for line in lines {
+            eprintln!("{}", line);
+        }
+        EXIT_CODE.store(ErrorCode::Usage.exit_code(), Ordering::Relaxed);
+    }
+}
+
+fn print_pairing_stats(stats: &PairingStats, filter: &StatsFilter) {
+    if stats.total_commits == 0 {
+        let filtered = filter.author.is_some()
+            || filter.coauthor.is_some()
+            || filter.branch.is_some()
+            || filter.path.is_some()
+            || filter.since.is_some()
+            || filter.until.is_some();
+        if filtered {
+            println!("No commits match the filters");
+        } else {
+            println!("No commits yet");
+        }
+        return;
+    }
+
+    let solo_commits = stats.total_commits - stats.paired_commits;
+    println!(
+        "{} commits, {} paired ({}%), {} solo ({}%)",
+        stats.total_commits,
+        stats.paired_commits,
+        stats.paired_commits * 100 / stats.total_commits,
+        solo_commits,
+        solo_commits * 100 / stats.total_commits
+    );
+    println!("\nPeople:");
+    for person in &stats.people {
+        println!(
+            "  {} <{}>: {} commits, {} paired, {} solo",
+            person.identity.name,
+            person.identity.email,
+            person.commits,
+            person.paired_commits,
+            person.commits - person.paired_commits
+        );
+    }
+    if !stats.pairs.is_empty() {
+        println!("\nPairs:");
+        for pair in &stats.pairs {
+            println!(
+                "  {} + {}: {} commits",
+                pair.first.name, pair.second.name, pair.commits
+            );
+        }
+    }
+}
+
+/// People and pairs listed by `stats --compare`
+const SHOWN_COMPARED: usize = 10;
+
+/// A markdown table, ready to paste into a sprint review
+fn print_velocity_report(intervals: &[VelocityInterval]) {
+    if intervals.is_empty() {
+        println!("No commits found");
+        return;
+    }
+    let cell = |text: &str| text.replace('|', "\\|");
+    println!("| Interval | Commits | Paired | Paired % | Combinations | Top pairs |");
+    println!("|---|---:|---:|---:|---:|---|");
+    for interval in intervals {
+        let stats = &interval.stats;
+        let percent = match stats.total_commits {
+            0 => "-".to_string(),
+            total => format!("{}%", stats.paired_commits * 100 / total),
+        };
+        let top_pairs: Vec<String> = stats
+            .pairs
+            .iter()
+            .take(3)
+            .map(|pair| {
+                cell(&format!(
+                    "{} + {} ({})",
+                    pair.first.name, pair.second.name, pair.commits
+                ))
+            })
+            .collect();
+        println!(
+            "| {} to {} | {} | {} | {} | {} | {} |",
+            interval.start,
+            interval.end,
+            stats.total_commits,
+            stats.paired_commits,
+            percent,
+            interval.combinations,
+            if top_pairs.is_empty() {
+                "-".to_string()
+            } else {
+                top_pairs.join(", ")
+            }
+        );
+    }
+}
+
+fn print_stats_comparison(a: &StatsWindow, b: &StatsWindow, comparison: &StatsComparison) {
+    let (stats_a, stats_b) = (&comparison.a, &comparison.b);
+    let percent = |stats: &PairingStats| match stats.total_commits {
+        0 => 0,
+        total => (stats.paired_commits * 100 / total) as i64,
+    };
+    let change = |a: i64, b: i64| match b - a {
+        0 => "=".to_string(),
+        diff => format!("{:+}", diff),
+    };
+    let pairing = |stats: &PairingStats| {
+        stats
+            .people
+            .iter()
+            .filter(|person| person.paired_commits > 0)
+            .count()
+    };
+
+    // (label, A, B, change); a section heading has no values
+    let mut rows: Vec<[String; 4]> = Vec::new();
+    let count = |label: &str, a: usize, b: usize| {
+        [
+            label.to_string(),
+            a.to_string(),
+            b.to_string(),
+            change(a as i64, b as i64),
+        ]
+    };
+    rows.push(count(
+        "Commits",
+        stats_a.total_commits,
+        stats_b.total_commits,
+    ));
+    rows.push(count(
+        "Paired commits",
+        stats_a.paired_commits,
+        stats_b.paired_commits,
+    ));
+    rows.push([
+        "Paired".to_string(),
+        format!("{}%", percent(stats_a)),
+        format!("{}%", percent(stats_b)),
+        match change(percent(stats_a), percent(stats_b)) {
+            diff if diff == "=" => diff,
+            diff => format!("{} pts", diff),
+        },
+    ]);
+    rows.push(count("People pairing", pairing(stats_a), pairing(stats_b)));
+    rows.push(count("Pairs", stats_a.pairs.len(), stats_b.pairs.len()));
+    for (heading, compared) in [
+        ("Paired commits per person:", &comparison.people),
+        ("Commits per pair:", &comparison.pairs),
+    ] {
+        if !compared.is_empty() {
+            rows.push([
+                heading.to_string(),
+                String::new(),
+                String::new(),
+                String::new(),
+            ]);
+        }
+        for row in compared.iter().take(SHOWN_COMPARED) {
+            rows.push(count(&format!("  {}", row.label), row.a, row.b));
+        }
+    }
+
+    let width = |column: usize| {
+        rows.iter()
+            .filter(|row| !row[1].is_empty())
+            .map(|row| row[column].chars().count())
+            .max()
+            .unwrap_or(0)
+    };
+    let (label_width, width_a, width_b) = (width(0), width(1).max(1), width(2).max(1));
+    println!("A: {}", a.label);
+    println!("B: {}", b.label);
+    println!(
+        "\n{:label_width$}  {:>width_a$}  {:>width_b$}  Change",
+        "", "A", "B"
+    );
+    for row in &rows {
+        if row[1].is_empty() {
+            println!("\n{}", row[0]);
+            continue;
+        }
+        println!(
+            "{:label_width$}  {:>width_a$}  {:>width_b$}  {}",
+            row[0], row[1], row[2], row[3]
+        );
+    }
+}
+
+fn print_pairing_time(times: &[PairTime]) {
+    if times.is_empty() {
+        return;
+    }
+
+    println!("\nTime together (estimated from commit times):");
+    for time in times {
+        println!(
+            "  {} + {}: {}h {:02}m over {} session{}",
+            time.first.name,
+            time.second.name,
+            time.minutes / 60,
+            time.minutes % 60,
+            time.sessions,
+            if time.sessions == 1 { "" } else { "s" }
+        );
+    }
+}
+
+fn print_heatmap(chart: &str) {
+    println!("\nActivity, one column per week:");
+    print!("{}", chart);
+    println!(
+        "    {} no commits  {} solo  {} some paired  {} all paired",
+        HEATMAP_CELLS[0], HEATMAP_CELLS[1], HEATMAP_CELLS[2], HEATMAP_CELLS[3]
+    );
+}
+
+/// First pairings listed by `stats --fun`
+const SHOWN_FIRST_PAIRINGS: usize = 10;
+
+fn print_pairing_streaks(streaks: &PairingStreaks) {
+    let days = |n: usize| format!("{} day{}", n, if n == 1 { "" } else { "s" });
+    println!("\nStreaks:");
+    println!(
+        "  🔥 Current: {} in a row with a paired commit",
+        days(streaks.current_streak)
+    );
+    println!("  🏆 Longest: {}", days(streaks.longest_streak));
+
+    if !streaks.first_pairings.is_empty() {
+        println!("\nFirst pairings:");
+        for milestone in streaks.first_pairings.iter().take(SHOWN_FIRST_PAIRINGS) {
+            println!(
+                "  🎉 {} {} + {} ({} {})",
+                milestone.date,
+                milestone.first.name,
+                milestone.second.name,
+                milestone.short_hash,
+                milestone.subject
+            );
+        }
+        let more = streaks
+            .first_pairings
+            .len()
+            .saturating_sub(SHOWN_FIRST_PAIRINGS);
+        if more > 0 {
+            println!("  ...and {} more", more);
+        }
+    }
+}
+
+fn print_roster_backups(backups: &[RosterBackup]) {
+    if backups.is_empty() {
+        println!("No roster backups yet");
+        return;
+    }
+    println!("Roster backups, newest first:");
+    for backup in backups {
+        let entries = match backup.entries {
+            Some(entries) => format!("{} entries", entries),
+            None => "encrypted".to_string(),
+        };
+        let managed = if backup.managed {
+            ", with managed roster"
+        } else {
+            ""
+        };
+        println!("  {} ({}{})", backup.name, entries, managed);
+    }
+}
+
+fn print_roster_sync(sync: &RosterSync) {
+    println!("Synced {} people from {}", sync.entries, sync.url);
+    let entry = |(alias, name, email): &RosterEntry| format!("{} ({} <{}>)", alias, name, email);
+    if !sync.first_sync {
+        if sync.added.is_empty() && sync.removed.is_empty() && sync.changed.is_empty() {
+            println!("No changes since the last sync");
+        }
+        for added in &sync.added {
+            println!("  + {}", entry(added));
+        }
+        for removed in &sync.removed {
+            println!("  - {}", entry(removed));
+        }
+        for (before, after) in &sync.changed {
+            println!(
+                "  ~ {}: {} <{}> -> {} <{}>",
+                after.0, before.1, before.2, after.1, after.2
+            );
+        }
+    }
+    if !sync.drift.is_empty() {
+        println!("\nYour local roster has drifted from it:");
+        for drift in &sync.drift {
+            println!("  {}", drift);
+        }
+    }
+}
+
+fn print_roster_changes(changes: &RosterChanges, verb: &str) {
+    let entry = |(alias, name, email): &RosterEntry| format!("{} ({} <{}>)", alias, name, email);
+    let direction = if verb == "Pushed" { "to" } else { "from" };
+    if changes.is_empty() {
+        println!("No changes {} {}", direction, changes.source);
+    } else {
+        println!(
+            "{} {} new and {} updated {} {}",
+            verb,
+            changes.added.len(),
+            changes.updated.len(),
+            direction,
+            changes.source
+        );
+    }
+    for added in &changes.added {
+        println!("  + {}", entry(added));
+    }
+    for (before, after) in &changes.updated {
+        println!(
+            "  ~ {}: {} <{}> -> {} <{}>",
+            after.0, before.1, before.2, after.1, after.2
+        );
+    }
+    for (kept, incoming) in &changes.kept {
+        println!(
+            "  = {}: kept {} <{}> over {} <{}>",
+            kept.0, kept.1, kept.2, incoming.1, incoming.2
+        );
+    }
+    for (skipped, reason) in &changes.skipped {
+        println!("  ! {} skipped: {}", entry(skipped), reason);
+    }
+}
+
+fn print_roster_import(import: &RosterImport, dry_run: bool, roster: &str) {
+    if import.added.is_empty() {
+        println!("Nobody new to add to the {}", roster);
+    } else {
+        println!(
+            "{} {} people to the {}:",
+            if dry_run { "Would add" } else { "Added" },
+            import.added.len(),
+            roster
+        );
+        for (alias, name, email) in &import.added {
+            println!("  {} -> {} <{}>", alias, name, email);
+        }
+    }
+    if !import.skipped.is_empty() {
+        println!("Skipped:");
+        for (login, reason) in &import.skipped {
+            println!("  {}: {}", login, reason);
+        }
+    }
+}
+
+fn print_team_sync(sync: &TeamSync, dry_run: bool) {
+    if sync.teams.is_empty() {
+        println!("No teams found");
+        return;
+    }
+    if sync.joined.is_empty() && sync.left.is_empty() {
+        println!("Team tags are up to date ({} teams)", sync.teams.len());
+    } else {
+        println!(
+            "{} team tags ({} teams):",
+            if dry_run { "Would update" } else { "Updated" },
+            sync.teams.len()
+        );
+        for (alias, team) in &sync.joined {
+            println!("  + {} joined {}", alias, team);
+        }
+        for (alias, team) in &sync.left {
+            println!("  - {} left {}", alias, team);
+        }
+    }
+    if !sync.unmatched.is_empty() {
+        println!("Not in the roster (add them with 'git-pair import'):");
+        for (username, team) in &sync.unmatched {
+            println!("  {} ({})", username, team);
+        }
+    }
+}
+
+fn print_coauthor_diff(diff: &CoauthorDiff) {
+    if diff.added.is_empty() && diff.removed.is_empty() {
+        if diff.kept.is_empty() {
+            println!("Neither {} nor {} has co-authors", diff.from, diff.to);
+        } else {
+            println!("{} and {} have the same co-authors", diff.from, diff.to);
+        }
+        return;
+    }
+    println!("Co-authors from {} to {}:", diff.from, diff.to);
+    for (mark, people) in [("+", &diff.added), ("-", &diff.removed), (" ", &diff.kept)] {
+        for person in people {
+            println!("  {} {} <{}>", mark, person.name, person.email);
+        }
+    }
+}
+
+fn print_roster_conflicts(conflicts: &[RosterConflict], pin_problems: &[String]) {
+    if !conflicts.is_empty() {
+        println!(
+            "Found {} alias conflict(s) across rosters:",
+            conflicts.len()
+        );
+    }
+    for conflict in conflicts {
+        let how = if conflict.pinned {
+            "pinned"
+        } else {
+            "first in chain"
+        };
+        println!(
+            "  {} resolves from {} ({})",
+            conflict.alias, conflict.winner, how
+        );
+        for (spec, name, email) in &conflict.definitions {
+            let mark = if *spec == conflict.winner { "*" } else { " " };
+            println!("    {} {}: {} <{}>", mark, spec, name, email);
+        }
+    }
+    if !pin_problems.is_empty() {
+        println!("Found {} problem(s) with pins:", pin_problems.len());
+    }
+    for problem in pin_problems {
+        println!("  {}", problem);
+    }
+    if conflicts.iter().any(|conflict| !conflict.pinned) {
+        println!("Pin an alias to a roster with 'git-pair pin <alias>=<provider>'");
+    }
+}
+
+fn print_amend(amend: &AmendReport, dry_run: bool) {
+    let credited = amend.commits.iter().filter(|c| c.credited).count();
+    if credited == 0 {
+        println!("Every commit already has co-author trailers, nothing to amend");
+        return;
+    }
+    println!(
+        "{} co-authors to {} commit{}:",
+        if dry_run { "Would add" } else { "Added" },
+        credited,
+        if credited == 1 { "" } else { "s" }
+    );
+    for commit in &amend.commits {
+        if commit.credited {
+            println!("  {} {}", commit.short_hash, commit.subject);
+        } else {
+            println!(
+                "  {} {} (already has co-author trailers)",
+                commit.short_hash, commit.subject
+            );
+        }
+    }
+    if !amend.pushed_to.is_empty() {
+        println!(
+            "{} already on {}, so the branch needs a force push{}",
+            if dry_run {
+                "Some of these commits are"
+            } else {
+                "Some of these commits were"
+            },
+            amend.pushed_to.join(", "),
+            if dry_run { " (and --force)" } else { "" }
+        );
+    }
+}
+
+fn print_unpaired_commits(commits: &[UnpairedCommit], limit: usize) {
+    if commits.is_empty() {
+        println!(
+            "Every commit made while pairing among the last {} has co-author trailers",
+            limit
+        );
+        return;
+    }
+
+    println!(
+        "{} of the last {} commits were made while pairing but have no co-author trailers:",
+        commits.len(),
+        limit
+    );
+    for commit in commits {
+        println!(
+            "  {} {}  {}  {}",
+            commit.short_hash, commit.date, commit.author.name, commit.subject
+        );
+    }
+    println!();
+    println!("The hook was probably skipped, e.g. by a GUI client that doesn't run hooks or a");
+    println!("hook manager that regenerated it. 'git-pair env' shows where hooks run from.");
+}
+
+fn print_hook_test(test: &HookTest) {
+    println!(
+        "Hook:     {}{}",
+        test.hook.display(),
+        if test.installed {
+            ""
+        } else {
+            " (not installed, ran what git-pair would install)"
+        }
+    );
+    println!("Source:   {}", test.source);
+    match (&test.branch, &test.config_file) {
+        (Some(branch), Some(config_file)) => println!(
+            "Config:   {} for branch '{}'{}",
+            config_file.display(),
+            branch,
+            if test.config_exists { "" } else { " (missing)" }
+        ),
+        _ => println!("Config:   none, no current branch (detached HEAD?)"),
+    }
+    if let Some(policy_file) = &test.policy_file {
+        println!("Policy:   {}", policy_file.display());
+    }
+    println!("Settings:");
+    for (key, value) in &test.settings {
+        match value {
+            Some(value) => println!("  {} = {}", key, value),
+            None => println!("  {} (default)", key),
+        }
+    }
+    if !test.output.is_empty() {
+        println!("Hook output:");
+        for line in &test.output {
+            println!("  {}", line);
+        }
+    }
+
+    println!();
+    if test.before == test.after {
+        println!("The hook left the message unchanged");
+        return;
+    }
+    for (marker, line) in diff_lines(&test.before, &test.after) {
+        println!("{} {}", marker, line);
+    }
+}
+
+fn print_doctor(checks: Result<Vec<DoctorCheck>, GitPairError>, fix: bool) {
+    let checks = match checks {
+        Ok(checks) => checks,
+        Err(e) => return report_error(&e),
+    };
+    for check in &checks {
+        let status = match check.status {
+            DoctorStatus::Ok => "ok",
+            DoctorStatus::Warning => "warning",
+            DoctorStatus::Error => "error",
+        };
+        println!("{:<8}{}: {}", status, check.name, check.message);
+        match &check.suggestion {
+            _ if check.fixed => println!("        fixed"),
+            Some(suggestion) => println!("        {}", suggestion),
+            None => {}
+        }
+    }
+
+    let remaining = checks
+        .iter()
+        .filter(|check| check.status == DoctorStatus::Error && !check.fixed)
+        .count();
+    if remaining > 0 {
+        let hint = if fix {
+            ""
+        } else {
+            ", see above for how to fix them"
+        };
+        report(
+            ErrorCode::Error,
+            &format!("{} problem(s) found{}", remaining, hint),
+        );
+    }
+}
+
+fn print_policy_report(report: &PolicyReport) {
+    if report.violations.is_empty() {
+        println!(
+            "{} commit(s) checked, all follow the repository policy",
+            report.commits
+        );
+        return;
+    }
+
+    for violation in &report.violations {
+        println!(
+            "  {} {}  {}",
+            violation.short_hash, violation.subject, violation.problem
+        );
+    }
+    let mut commits: Vec<&str> = report
+        .violations
+        .iter()
+        .map(|violation| violation.short_hash.as_str())
+        .collect();
+    commits.dedup();
+    crate::report(
+        ErrorCode::Policy,
+        &format!(
+            "{} of {} commit(s) break the repository policy",
+            commits.len(),
+            report.commits
+        ),
+    );
+}
+
+fn print_pairing_check(check: &PairingCheck) {
+    if check.unpaired.is_empty() {
+        println!(
+            "{} commit(s) checked, all have a co-author from the roster",
+            check.commits
+        );
+        return;
+    }
+
+    for commit in &check.unpaired {
+        println!(
+            "  {} {} {}  {}",
+            commit.short_hash, commit.date, commit.author.name, commit.subject
+        );
+    }
+    report(
+        ErrorCode::Error,
+        &format!(
+            "{} of {} commit(s) have no co-author from the roster",
+            check.unpaired.len(),
+            check.commits
+        ),
+    );
+}
+
+fn print_recent_pairings(pairings: &[RecentPairing]) {
+    if pairings.is_empty() {
+        println!("No recent pairings yet");
+        return;
+    }
+
+    println!("Recent pairings:");
+    for (index, pairing) in pairings.iter().enumerate() {
+        let names: Vec<&str> = pairing.coauthors.iter().map(|p| p.name.as_str()).collect();
+        println!(
+            "  {}. {} (last used {})",
+            index + 1,
+            names.join(", "),
+            pairing.last_used_date()
+        );
+    }
+    println!("Use 'git-pair recent --use <number>' to pair with one of them again.");
+}
+
+fn print_blame(lines: &[BlameLine]) {
+    let credits: Vec<String> = lines
+        .iter()
+        .map(|line| {
+            let names: Vec<&str> = line.people.iter().map(|p| p.name.as_str()).collect();
+            names.join(" & ")
+        })
+        .collect();
+    let width = credits.iter().map(|c| c.chars().count()).max().unwrap_or(0);
+    let number_width = lines.len().to_string().len();
+    for (line, credit) in lines.iter().zip(&credits) {
+        let hash = if line.short_hash.is_empty() {
+            "0000000"
+        } else {
+            &line.short_hash
+        };
+        println!(
+            "{} ({:<width$} {} {:>number_width$}) {}",
+            hash, credit, line.date, line.line_number, line.content
+        );
+    }
+}
+
+fn print_standup(since: &str, groups: &[StandupGroup]) {
+    if groups.is_empty() {
+        println!("No commits since {}", since);
+        return;
+    }
+
+    for (i, group) in groups.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        if group.partners.is_empty() {
+            println!("Solo:");
+        } else {
+            let names: Vec<&str> = group.partners.iter().map(|p| p.name.as_str()).collect();
+            println!("With {}:", names.join(", "));
+        }
+        for commit in &group.commits {
+            println!("- {} ({})", commit.subject, commit.short_hash);
+        }
+    }
+}
+
+fn print_alias_info(info: &AliasInfo) {
+    println!("{}", info.alias);
+    println!("  name:        {}", info.name);
+    println!("  email:       {}", info.email);
+    if let Some(display_name) = &info.metadata.display_name {
+        println!("  goes by:     {}", display_name);
+    }
+    if let Some(pronouns) = &info.metadata.pronouns {
+        println!("  pronouns:    {}", pronouns);
+    }
+    if let Some(timezone) = &info.metadata.timezone {
+        println!("  timezone:    {}", timezone);
+    }
+    if info.branches.is_empty() {
+        println!("  branches:    none");
+    } else {
+        println!("  branches:    {}", info.branches.join(", "));
+    }
+    match &info.last_paired {
+        Some(last) => println!(
+            "  last paired: {} ({} {})",
+            last.date, last.short_hash, last.subject
+        ),
+        None => println!("  last paired: never"),
+    }
+}
+
+fn print_roster_merges(verb: &str, merges: &[RosterMerge]) {
+    for merge in merges {
+        println!(
+            "{} {} into '{}' <{}>",
+            verb,
+            merge
+                .removed_aliases
+                .iter()
+                .map(|a| format!("'{}'", a))
+                .collect::<Vec<_>>()
+                .join(", "),
+            merge.kept_alias,
+            merge.email
+        );
+    }
+}
+
+/// `git pair onboarding`: walks a new user through the first-run setup, one question
+/// at a time. Every step uses the same functions as the commands it stands for, so
+/// what it sets up can be changed later with them.
+fn run_onboarding() -> Result<(), GitPairError> {
+    if !io::stdin().is_terminal() {
+        return Err(GitPairError::Usage(
+            "onboarding asks questions, so it needs a terminal. Run 'git-pair init' and 'git-pair add' instead."
+                .to_string(),
+        ));
+    }
+    // Answers and messages are indented under the step they belong to
+    let say = |message: &str| {
+        for line in message.lines() {
+            println!("  {}", line);
+        }
+    };
+    println!("Setting up git-pair in five steps. Press Enter to skip a question.");
+
+    println!("\nStep 1/5: Your git identity");
+    match git_identity()? {
+        Some(me) => println!("  Commits are made as {} <{}>", me.name, me.email),
+        None => {
+            println!("  git doesn't know who you are yet");
+            let name = prompt("  Your name")?;
+            let email = prompt("  Your email")?;
+            say(&set_git_identity(&name, &email)?);
+        }
+    }
+
+    println!("\nStep 2/5: Where to find the people you pair with");
+    println!("  Your roster has {} people", get_roster()?.len());
+    let repo_roster = repo_roster_file()?;
+    let mut sources = Vec::new();
+    if let Some(path) = &repo_roster {
+        sources.push((
+            "repo",
+            format!("The repository's roster ({})", path.display()),
+        ));
+    }
+    sources.push((
+        "log",
+        "People who have committed to this repository".to_string(),
+    ));
+    sources.push((
+        "csv",
+        "A CSV file of name,email or alias,name,email rows".to_string(),
+    ));
+    for (number, (_, label)) in sources.iter().enumerate() {
+        println!("  {}. {}", number + 1, label);
+    }
+    let choices = prompt("  Use which, e.g. 1,2")?;
+    for choice in choices.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+        let Some((source, _)) = choice
+            .parse::<usize>()
+            .ok()
+            .and_then(|number| sources.get(number.wrapping_sub(1)))
+        else {
+            println!("  There's no choice '{}'", choice);
+            continue;
+        };
+        let result = match *source {
+            "repo" => use_repo_roster().map(|message| say(&message)),
+            "log" => {
+                onboarding_import(|dry_run| import_repo_authors(DEFAULT_LOG_IMPORT_LIMIT, dry_run))
+            }
+            _ => match prompt("  CSV file")? {
+                file if file.is_empty() => Ok(()),
+                file => onboarding_import(|dry_run| import_roster_csv(Path::new(&file), dry_run)),
+            },
+        };
+        if let Err(e) = result {
+            say(&friendly_message(&e));
+        }
+    }
+
+    println!("\nStep 3/5: This repository");
+    say(&init_pair_config_with(None)?);
+
+    println!("\nStep 4/5: Who you usually pair with");
+    let roster = get_roster()?;
+    if roster.is_empty() {
+        println!(
+            "  The roster is empty; add people with 'git-pair add --global <alias> <name> <email>'"
+        );
+    }
+    while !roster.is_empty() {
+        let coauthors = get_coauthors().unwrap_or_default();
+        let alias = prompt_alias(
+            "  Alias (Tab completes, Enter when done)",
+            &roster,
+            &coauthors,
+        )?;
+        if alias.is_empty() {
+            break;
+        }
+        match add_coauthor_from_global_with(&alias, &AddOptions::default()) {
+            Ok(message) => say(&message),
+            Err(e) => say(&friendly_message(&e)),
+        }
+    }
+
+    println!("\nStep 5/5: Checking the hook with a test commit");
+    let test = simulate_hook(None, None)?;
+    let added: Vec<&str> = diff_lines(&test.before, &test.after)
+        .into_iter()
+        .filter(|(change, _)| *change == '+')
+        .map(|(_, line)| line)
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if added.is_empty() {
+        println!(
+            "  The hook didn't change the test commit's message; 'git-pair hook test' shows why"
+        );
+    } else {
+        println!("  The hook adds to your commits:");
+        for line in added {
+            println!("    {}", line);
+        }
+    }
+
+    println!("\nAll set. Commit as usual; 'git-pair status' shows who you're pairing with.");
+    Ok(())
+}
+
+/// Shows who an import would add, and adds them once confirmed
+fn onboarding_import(
+    import: impl Fn(bool) -> Result<RosterImport, GitPairError>,
+) -> Result<(), GitPairError> {
+    let planned = import(true)?;
+    print_roster_import(&planned, true, "global roster");
+    if !planned.added.is_empty() && confirm("  Add them?")? {
+        print_roster_import(&import(false)?, false, "global roster");
+    }
+    Ok(())
+}
+
+/// Asks a yes/no question, defaulting to no. `--yes` (or `GIT_PAIR_ASSUME_YES=1`)
+/// answers yes; without it, a stdin that isn't a terminal is an error rather than a
+/// prompt nobody will answer, so scripts and CI fail instead of hanging.
+fn confirm(question: &str) -> Result<bool, GitPairError> {
+    if env::var("GIT_PAIR_ASSUME_YES").as_deref() == Ok("1") {
+        return Ok(true);
+    }
+    if !io::stdin().is_terminal() {
+        return Err(GitPairError::Usage(format!(
+            "Can't ask '{}' because stdin is not a terminal. Pass --yes before the command to go ahead.",
+            question
+        )));
+    }
+    let answer = prompt(&format!("{} [y/N]", question))?;
+    Ok(matches!(answer.to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Removes `name` from `args`, returning whether it was there
+fn take_flag(args: &mut Vec<String>, name: &str) -> bool {
+    let before = args.len();
+    args.retain(|arg| arg != name);
+    args.len() != before
+}
+
+/// Removes `<name> <value>` from the arguments and returns the value, if present
+fn take_option(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let index = args.iter().position(|a| a == name)?;
+    if index + 1 < args.len() {
+        let value = args.remove(index + 1);
+        args.remove(index);
+        Some(value)
+    } else {
+        args.remove(index);
+        None
+    }
+}
+
+/// Reads a line without echoing it when stdin is a terminal; piped input is read as is,
+/// e.g. `echo "$TOKEN" | git pair auth login github`
+fn prompt_secret(label: &str) -> Result<String, GitPairError> {
+    if !io::stdin().is_terminal() {
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .map_err(|e| GitPairError::io("reading input", e))?;
+        return Ok(input.trim().to_string());
+    }
+    let _ = Command::new("stty")
+        .arg("-echo")
+        .stdin(Stdio::inherit())
+        .status();
+    let result = prompt(label);
+    let _ = Command::new("stty")
+        .arg("echo")
+        .stdin(Stdio::inherit())
+        .status();
+    println!();
+    result
+}
+
+fn prompt(label: &str) -> Result<String, GitPairError> {
+    print!("{}: ", label);
+    io::stdout()
+        .flush()
+        .map_err(|e| GitPairError::io("writing prompt", e))?;
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| GitPairError::io("reading input", e))?;
+    Ok(input.trim().to_string())
+}
+
+/// Adds roster aliases to the current branch, reporting the ones that fail
+fn add_aliases(aliases: &[String], options: &AddOptions) {
+    let mut messages = Vec::new();
+    for alias in aliases {
+        match add_coauthor_from_global_with(alias, options) {
+            Ok(message) => messages.push(message),
+            Err(e) => report_error(&e),
+        }
+    }
+    print_change(&messages.join("\n"));
+}
+
+/// Lists the roster by number and reads which people to pick, for `add` without
+/// arguments and `init --with`. Typing part of an alias or name narrows the list;
+/// the numbers stay the same. Enter alone picks nobody.
+fn pick_roster_aliases() -> Result<Vec<String>, GitPairError> {
+    let mut roster = get_roster()?;
+    let archived = get_archived_aliases().unwrap_or_default();
+    roster.retain(|(alias, _, _)| !archived.contains(alias));
+    if roster.is_empty() {
+        return Err(GitPairError::InvalidState(
+            "The roster is empty. Add people with 'git-pair add --global <alias> <name> <email>'."
+                .to_string(),
+        ));
+    }
+    let coauthors = get_coauthors().unwrap_or_default();
+    let mut filter = String::new();
+    loop {
+        let shown = complete_alias(&roster, &coauthors, &filter);
+        if shown.is_empty() {
+            println!("Nobody in the roster matches '{}'", filter);
+            filter.clear();
+            continue;
+        }
+        for completion in &shown {
+            let number = roster
+                .iter()
+                .position(|(alias, _, _)| *alias == completion.alias)
+                .map_or(0, |index| index + 1);
+            if completion.added {
+                println!(
+                    "  \x1b[2m{:>3}. {:<12} {} (added)\x1b[0m",
+                    number, completion.alias, completion.name
+                );
+            } else {
+                println!(
+                    "  {:>3}. {:<12} {}",
+                    number, completion.alias, completion.name
+                );
+            }
+        }
+        let input = prompt("Numbers to add (e.g. 1 3 or 2-4), or text to filter")?;
+        if input.is_empty() {
+            return Ok(Vec::new());
+        }
+        if input
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, ' ' | ',' | '-'))
+        {
+            let picked = parse_roster_selection(&input, roster.len())?;
+            return Ok(picked
+                .into_iter()
+                .map(|index| roster[index].0.clone())
+                .collect());
+        }
+        filter = input;
+    }
+}
+
+/// Reads an alias with Tab completion from the roster. The terminal is switched out of
+/// line mode with `stty` for the duration; when stdin isn't a terminal or `stty` isn't
+/// available this is a plain prompt.
+fn prompt_alias(
+    label: &str,
+    roster: &[RosterEntry],
+    coauthors: &[String],
+) -> Result<String, GitPairError> {
+    if !io::stdin().is_terminal() {
+        return prompt(label);
+    }
+    let saved = match Command::new("stty")
+        .arg("-g")
+        .stdin(Stdio::inherit())
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        }
+        _ => return prompt(label),
+    };
+    let raw = Command::new("stty")
+        .args(["-icanon", "-echo", "-isig", "min", "1"])
+        .stdin(Stdio::inherit())
+        .status();
+    if !raw.is_ok_and(|status| status.success()) {
+        return prompt(label);
+    }
+
+    let result = edit_alias_line(label, roster, coauthors);
+    let _ = Command::new("stty")
+        .arg(&saved)
+        .stdin(Stdio::inherit())
+        .status();
+    println!();
+    result
+}
+
+fn edit_alias_line(
+    label: &str,
+    roster: &[RosterEntry],
+    coauthors: &[String],
+) -> Result<String, GitPairError> {
+    let redraw = |line: &str| {
+        print!("\r\x1b[K{}: {}", label, line);
+    };
+    let mut line = String::new();
+    let mut pending: Vec<u8> = Vec::new();
+    let mut stdin = io::stdin().lock();
+    let mut byte = [0u8; 1];
+    redraw(&line);
+
+    loop {
+        io::stdout()
+            .flush()
+            .map_err(|e| GitPairError::io("writing prompt", e))?;
+        let read = stdin
+            .read(&mut byte)
+            .map_err(|e| GitPairError::io("reading input", e))?;
+        if read == 0 {
+            return Ok(line.trim().to_string());
+        }
+
+        match byte[0] {
+            b'\r' | b'\n' => return Ok(line.trim().to_string()),
+            // Ctrl-C, or Ctrl-D on an empty line
+            0x03 => return Err(GitPairError::Cancelled),
+            0x04 if line.is_empty() => return Err(GitPairError::Cancelled),
+            0x7f | 0x08 => {
+                if line.pop().is_some() {
+                    print!("\x08 \x08");
+                }
+            }
+            b'\t' => {
+                let completions = complete_alias(roster, coauthors, line.trim());
+                let prefix = common_alias_prefix(&completions);
+                match completions.as_slice() {
+                    [] => print!("\x07"),
+                    [only] => {
+                        line = only.alias.clone();
+                        redraw(&line);
+                    }
+                    _ if prefix.len() > line.len()
+                        && prefix.to_lowercase().starts_with(&line.to_lowercase()) =>
+                    {
+                        line = prefix;
+                        redraw(&line);
+                    }
+                    _ => {
+                        println!();
+                        for completion in &completions {
+                            if completion.added {
+                                println!(
+                                    "  \x1b[2m{:<12} {} (added)\x1b[0m",
+                                    completion.alias, completion.name
+                                );
+                            } else {
+                                println!("  {:<12} {}", completion.alias, completion.name);
+                            }
+                        }
+                        redraw(&line);
+                    }
+                }
+            }
+            // Escape sequences such as arrow keys are ignored
+            0x1b => {
+                let mut sequence = [0u8; 2];
+                let _ = stdin.read(&mut sequence);
+            }
+            byte if byte < 0x20 => {}
+            byte => {
+                pending.push(byte);
+                if let Ok(text) = std::str::from_utf8(&pending) {
+                    print!("{}", text);
+                    line.push_str(text);
+                    pending.clear();
+                } else if pending.len() >= 4 {
+                    pending.clear();
+                }
+            }
+        }
+    }
+}
+
+/// Asks for a co-author's name and email, suggesting and completing the email from
+/// people who have committed to this repository
+fn prompt_coauthor() -> Result<(String, String, String), GitPairError> {
+    let name = prompt("First name")?;
+    let surname = prompt("Last name")?;
+    if name.is_empty() || surname.is_empty() {
+        return Err(GitPairError::InvalidValue(
+            "Name and last name are required".to_string(),
+        ));
+    }
+
+    let authors = get_repo_authors().unwrap_or_default();
+    let full_name = format!("{} {}", name, surname);
+    let email = match complete_email(&authors, &full_name, "") {
+        Some(suggestion) => {
+            let input = prompt(&format!("Email [{}]", suggestion))?;
+            if input.is_empty() {
+                suggestion
+            } else {
+                input
+            }
+        }
+        None => prompt("Email")?,
+    };
+
+    let email = match complete_email(&authors, &full_name, &email) {
+        Some(completed) if completed != email => {
+            println!("Completed email to {}", completed);
+            completed
+        }
+        _ => email,
+    };
+    if email.is_empty() {
+        return Err(GitPairError::InvalidValue("Email is required".to_string()));
+    }
+
+    Ok((name, surname, email))
+}
+
+fn print_help() {
+    print!("{}", help_text());
+}
+
+/// Prints the lines of the help's command list about `command`, or reports that
+/// there's no such command
+fn print_command_help(command: &str) {
+    let help_text = help_text();
+    let mut lines = Vec::new();
+    let (mut in_commands, mut in_command) = (false, false);
+    for line in help_text.lines() {
+        if !line.starts_with(' ') {
+            in_commands = line == "COMMANDS:";
+            continue;
+        }
+        // An entry starts with its command; lines indented further continue it
+        if let Some(entry) = line.strip_prefix("    ").filter(|e| !e.starts_with(' ')) {
+            in_command = entry.split([' ', ',']).next() == Some(command);
+        }
+        if in_commands && in_command {
+            lines.push(line);
+        }
+    }
+    if lines.is_empty() {
+        usage(&[
+            &format!("Unknown command: {}", command),
+            "Use 'git-pair --help' for usage information.",
+        ]);
+    } else {
+        println!("{}", lines.join("\n"));
+    }
+}
+
+fn help_text() -> String {
+    format!(
+        r#"git-pair {}
+A git extension for pair programming with per-branch co-author management
+
+USAGE:
+    git-pair [-C <path>] [--offline] [--yes] [--verbose] [--json] <COMMAND>
+
+OPTIONS:
+    -C, --repo <path>                       Work on the repository at <path> instead of the current one
+    --offline                               Never use the network; remote rosters are skipped
+    -y, --yes                               Answer yes to confirmations; without it they fail
+                                            when stdin is not a terminal
+    --verbose                               Explain on stderr which git commands run, which files are
+                                            written and what the hook decided (also RUST_LOG=git_pair=debug)
+    --json                                  Report errors as JSON on stderr, and status, list, add, remove, clear and stats results on stdout
+    --json-schema <command>                 Print the JSON Schema for a command's --json output
+
+COMMANDS:
+    init [--template <file>]                Initialize git-pair for current branch, optionally with a
+                                            config header template
+          [--with [<alias>...]]             Then add these co-authors, or pick them from the roster
+    init --from-default                     Initialize current branch with the default co-authors
+    set-default [--clear]                   Make current branch's co-authors the default for branches
+                                            without a config of their own, or remove the default
+    onboarding                              Set up identity, roster, repository and partners step by step
+    add <name> <surname> <email>            Add a co-author to current branch
+    add <alias>                             Add co-author from global roster
+    add                                     Pick co-authors from a numbered list of the roster
+    add --interactive                       Prompt for a roster alias (Tab completes) or a new co-author
+    add --github <user> [<name>]            Add a GitHub user by their profile name and noreply address
+    add --gitlab <user> [<name>]            Add a GitLab user [--host <host>] for a self-managed GitLab
+    add --global|--repo --github <user>     Add a GitHub (or --gitlab) user to the roster, under
+          [<alias>]                         their lowercased username unless an alias is given
+    add --team <slug>                       Add everyone in the roster tagged team:<slug>
+          [--style <style>]                 Trailer style: co-authored (default) or co-developed
+          [--trailer <kind>]                Credit them with another trailer: signed-off-by or reviewed-by
+          [--reviewer]                      Same as --trailer reviewed-by
+          [--dry-run]                       Show the files that would change without writing them
+          [--once]                          Credit them on the next commit only
+          [--for <duration>]                Stop crediting them after a while, e.g. 8h or 1d
+          [--force]                         Accept an email that doesn't look valid, or one already
+                                            on the branch under another name
+    import --github-org <org>               Add an organization's members to the global roster
+          [--team <slug>] [--dry-run]       Only one team's members, or only show who would be added
+    import --gitlab-group <group>           Add a GitLab group's members to the global roster
+          [--host <host>] [--dry-run]       From a self-managed GitLab instead of gitlab.com
+    team sync --github-org <org>            Tag roster entries with team:<slug> for their teams
+          [--gitlab-group <group>]          Or a GitLab group's subgroups [--host <host>] [--dry-run]
+    import --csv <file> [--dry-run]         Add the people in a CSV file (name,email or alias,name,email)
+    import-history [--since <when>]         Add everyone who authored, committed or co-authored a commit,
+          [--all-branches] [--repo]         once confirmed, to the global (or repository) roster
+          [--dry-run]                       Only show who would be added
+    import --from-log [--limit <n>]         Add this repository's most frequent commit authors
+    add --global <alias> <name> [<email>]   Add co-author to global roster (placeholder email if omitted)
+    add --repo <alias> <name> [<email>]     Add to the repository's committed .git-pair-roster instead
+          [--force]                         Update the alias if it already exists, or accept an
+                                            email that doesn't look valid
+          [--allow-duplicate-email]         Allow an email already used by another alias
+          [--pronouns <p>] [--timezone <tz>] Also store pronouns, timezone or [--display-name <name>]
+    remove --global <alias>                 Remove an alias from global roster
+    update --global <alias> [--name <name>] [--email <email>]
+                                            Correct a roster entry and the branches using it
+    rename --global <old> <new>             Rename an alias in global roster
+    archive --global <alias>...             Hide departed colleagues from listings and completion
+          [--unused <months>] [--dry-run]   Archive everyone you haven't paired with for that long
+    unarchive --global <alias>              Show an archived entry again
+    which <alias>                           Show which roster entry an alias resolves to
+    pin [<alias>=<provider>]                Resolve an alias from one roster provider here
+          [--clear <alias>]                 Remove a pin
+    diff [<branch-a> [<branch-b>]]          Show co-authors added/removed between two branches
+    info <alias>                            Show a roster entry, its branches and when you last paired
+    remove <name|email|alias> [--dry-run]   Remove a specific co-author from current branch
+    clear [--dry-run]                       Remove all co-authors from current branch
+    amend [--last <n> | --since <ref>]      Add the branch's co-authors to commits already made (HEAD by default)
+          [--force] [--dry-run]             Also rewrite pushed commits, or only show what would change
+    adopt [--last <n>] [--roster]           Take co-authors from the trailers of the branch's last 20 commits
+          [--dry-run]                       (or <n>), adding unknown people to the roster with --roster
+    squash-msg [<branch>] [--dry-run]       After 'git merge --squash', add the merged branch's co-authors
+                                            to the squash message
+    pause                                   Stop adding co-authors on current branch, keeping the list
+    solo                                    Same as pause, until 'git-pair resume'
+    resume                                  Start adding co-authors again after 'pause' or 'snooze'
+    snooze [<duration>]                     Stop adding co-authors for a while, e.g. 2h, then resume by itself
+    expire --after <duration>               Stop crediting the branch's co-authors after a while, e.g. 8h
+          [--never]                         Or take the expiry away
+    renew [<duration>]                      Extend the expiry, by the last duration unless one is given
+    once <alias>... -- <command>...         Run a command crediting those co-authors instead
+    commit --with <alias>... [-- <args>]    Run git commit crediting those co-authors on that commit only,
+                                            without a branch config or the hook
+    prompt [--starship]                     Print co-authors' first names for a shell prompt
+          [--format <format>]               Fill in {{names}}, {{initials}}, {{count}} and {{paused}}
+    message                                 Print the co-author trailers for the next commit
+    footer [<text>|--clear]                 Show or set a block the hook adds next to the trailers
+    enforce [on|off]                        Reject commits on a paired branch that lack its trailers
+    enforce enable [--branches <pattern>]   Same as on, also requiring a roster co-author on matching branches
+    enforce disable                         Same as off
+    verify [<range>] [--branch <name>]      Check commits against .git-pair-policy.toml, for CI
+    check <range>                           List commits without a co-author from the roster, for CI
+    status                                  Show current branch co-authors
+          [--audit] [--limit <n>]           Flag recent commits made while pairing that lack trailers
+    list --global [--all]                   Show global roster, with --all including archived entries
+    list --repo                             Show the repository's committed roster
+    list --repos                            Show registered repositories with active co-authors
+    stats                                   Show how often people paired in this branch's history
+          [--time] [--gap <minutes>]        Also estimate time spent per pair from commit times
+          [--heatmap]                       Also chart paired and solo commits per day over the last year
+          [--fun]                           Also show pairing streaks and first-time pairings
+          [--author <email|me>]             Only count commits authored by this email, or by you
+          [--coauthor <alias>]              Only count commits crediting this co-author
+          [--branch <glob>] [--path <glob>] Read matching branches, or only commits touching matching paths
+          [--since <when>] [--until <when>] Only count commits made in that window, e.g. --since 30d
+          [--csv]                           Print the counts as CSV (--json prints them as JSON)
+          --compare --period <length>       Compare the last week, sprint, month or e.g. 10d with the one before
+          --compare --since-a <when>        Compare two windows side by side; A ends where B starts
+            --since-b <when>                unless --until-a is given
+          --report velocity                 Print paired share and top pairs per sprint as a Markdown table
+            [--interval <length>]           Interval length: week, sprint (default), month or e.g. 2w
+    graph [--format dot|mermaid]            Print the pairing network for graphviz or Mermaid
+    blame <file>                            Show who last changed each line, co-authors included
+    standup [--since <when>]                List your commits since yesterday, grouped by pair
+    rotate [--schedule <file>]              Set current branch co-authors from the rotation schedule
+          [--date <YYYY-MM-DD>]             Use the pairing scheduled for another day
+    mob start                               Start or join a mob session on the WIP branch mob/<branch>
+    mob start [--minutes <n>] <alias>...    Take turns driving on this machine, in this order, with a timer
+    mob next                                Commit work in progress, push it and switch back for the next driver
+                                            (or hand the keyboard to the next in a rotation)
+    mob status                              Show the driver, who is next and the time left in the turn
+    mob done [-m <message>]                 Squash the session into one commit crediting every participant
+    env                                     Show the paths, hooks directory and overrides in effect
+    setup-alias [--remove] [--force]        Add git p, git pairs and git solo to your global git config
+    cache clear                             Delete cached GitHub and roster responses
+    auth login <provider>                   Store a github, gitlab or directory token in the OS keychain
+    auth logout <provider>                  Remove a stored token
+    auth status                             Show where each provider's token comes from
+    pr-body [--base <ref>]                  Print Co-authored-by lines for a pull request description
+          [--template <file>]               Fill {{co-authors}} in a PR template, or append to it
+    announce [--format slack|markdown|plain] Print who is pairing on this branch, ready to paste into chat
+    share [--qr]                            Print the branch's pairing as a snippet, or a QR code, for 'apply'
+    apply [--dry-run] <snippet|file|url|->  Set up the pairing in a 'share' snippet on this branch, after confirming
+    events [--follow [--new]]               Print co-author changes as JSON lines, and keep printing new ones
+    roster lint                             Check global roster for problems
+    roster dedupe [--dry-run]               Merge roster entries that share an email, after confirming
+    roster backup                           Snapshot the global and managed rosters
+    roster restore [<backup>] [--list]      Put back the newest or a named roster backup, or list them
+    roster sync [<url>]                     Pull the managed roster from a company endpoint and report drift
+          [--cert <file>] [--key <file>]    Client certificate for endpoints that require mTLS
+    roster sync --from <source>             Merge a shared roster (git repo, URL or file) into yours
+          [--prefer remote|local|ask]       Which entry wins for an alias both define (default: remote)
+          [--dry-run]                       Show what would change without writing the roster
+    roster push [--to <git remote>]         Add your roster's entries to the shared roster and push it
+    roster encrypt [--recipient <key>]...   Encrypt the global roster with age, or a passphrase without keys
+    roster decrypt                          Turn an encrypted global roster back into a plain file
+    save-set <name> [--force]               Save current branch co-authors as a named preset
+    use [<name>]                            Apply a saved preset to the current branch, or list presets
+    group add <name> <alias>... [--force]   Define a pair group of roster aliases
+    group remove <name>                     Delete a pair group
+    group list                              List pair groups
+    group apply <name>                      Add a group's members to the current branch (or add @<name>)
+    driver [<alias>]                        Make a roster member the commit author, or show who is
+    driver rotate                           Hand the driver role to the next co-author
+    driver --reset                          Go back to authoring as your global git identity
+    recent [--use <number>]                 List recent co-author combinations, or pair with one again
+    preview [-m <message>]                  Show the commit message the hook would produce
+    hook test [--message-file <file>]       Run the hook on a sample message, showing the diff and what it read
+          [--source <source>]               As if git passed this commit source, e.g. message
+    edit                                    Edit the current branch config in $EDITOR
+    check-config [--fix]                    Check git-pair files and the roster, optionally repairing them
+          [--dry-run]                       With --fix, show what would be repaired without writing
+    doctor [--fix]                          Check the repository, branch config, hooks and roster, and
+                                            suggest or (with --fix) make repairs
+    repair-hook                             Reinstall the git-pair hook if it is missing or modified
+    upgrade-hooks [--all]                   Refresh the hook here, or in all registered repositories
+    clean-hooks [--dry-run]                 Remove leftover git-pair sections from the hooks git runs
+    install --mode <hook|template>          Add co-authors with the hook, or with commit.template
+    install-hooks --manager <tool> [--apply]
+                                            Print or apply hook setup for plain, husky, lefthook,
+                                            pre-commit or overcommit
+    hook-impl prepare-commit-msg <msgfile>  Run the hook logic for a hook manager such as pre-commit
+    status --porcelain                      Print branch status in a stable format for scripts
+    list --global --porcelain               Print the global roster in a stable format for scripts
+    list --branches                         Show which branches have a git-pair config, and their co-authors
+    prune [--dry-run]                       Delete the configs of branches that no longer exist, after confirming
+    move <old-branch> <new-branch>          Move a branch's config to a renamed branch (done on its own
+                                            after 'git branch -m')
+    config [<key> [<value>]]                Show or change repository settings
+    config --unset <key>                    Reset a repository setting to its default
+    completions <bash|zsh|fish|powershell>  Print a shell completion script; roster aliases are
+                                            completed for add, remove and other commands taking them
+    help [<command>], --help, -h            Show this help message, or its lines about a command
+                                            (also 'git-pair <command> --help')
+    --version, -V                           Show version information
+
+SETTINGS (git-pair config):
+    trailer-key                             Trailer used for co-authors (default: Co-authored-by)
+    trailer-order                           insertion (default), alphabetical or rotate
+    max-coauthors                           Maximum number of co-authors per branch
+    max-coauthors-policy                    warn (default) or refuse when over the maximum
+    hook-mode                               merge (default) or chain to run an existing hook separately
+    hook-engine                             shell (default), binary to have the hook call git-pair,
+                                            or external when a hook manager runs hook-impl
+    commit-sources                          Commit sources that get co-authors (default: editor,message)
+    check-emails                            off (default) or warn when adding an email that never committed here
+    checkout-hook                           off (default), show or seed to report pairing after checkout
+    enforce                                 off (default) or on to reject commits missing co-authors
+    enforce-branches                        Branch patterns where every commit needs a roster co-author
+    roster-providers                        Where aliases are looked up, in order (default: local)
+    auto-pair                               Co-authors for new branches, e.g. "feature/pay-* => alice,bob"
+    offline                                 off (default) or on to never use the network
+    notify                                  off (default) or on for desktop notifications on rotations
+                                            and mob handovers
+    session-gap                             Minutes between commits that end a stats --time session (default: 120)
+    config-header                           Comment under the first line of branch configs ({{branch}}, {{date}}, {{repo}})
+    hook-header                             Comment at the top of the hook section ({{date}}, {{repo}})
+
+ENVIRONMENT VARIABLES:
+    GIT_PAIR_CONFIG_FILE                    Override global config file location (per-directory sections)
+    GIT_PAIR_ROSTER_FILE                    Override global roster file location
+    GIT_PAIR_MANAGED_ROSTER_FILE            Override managed roster file location
+    GIT_PAIR_AGE_IDENTITY                   age identity file that decrypts an encrypted roster
+    GIT_PAIR_AGE                            age-compatible program for roster encryption (default: age)
+    GIT_PAIR_ROSTER_SYNC_INTERVAL           Seconds before lookups sync the managed roster again (default: 86400)
+    GIT_PAIR_ROSTER_BACKUPS                 Roster backups to keep (default: 10)
+    GIT_PAIR_REPOS_FILE                     Override repository registry file location
+    GIT_PAIR_SCHEDULE_FILE                  Override rotation schedule file location
+    GIT_PAIR_RECENT_FILE                    Override recent pairings file location
+    GIT_PAIR_ARCHIVE_FILE                   Override archived aliases file location
+    GIT_PAIR_PRESETS_FILE                   Override presets file location
+    GIT_PAIR_GROUPS_FILE                    Override pair groups file location
+    GIT_PAIR_CONFIG_DIR                     Keep per-repository state here instead of .git/git-pair
+    GIT_PAIR_OFFLINE                        Set to 1 for offline mode, like --offline
+    GIT_PAIR_ASSUME_YES                     Set to 1 to answer yes to confirmations, like --yes
+    GIT_PAIR_VERBOSE                        Set to 1 for debug output, like --verbose
+    GIT_PAIR_DISABLE                        Set to 1 to commit without co-authors
+    GIT_PAIR_NO_ENFORCE                     Set to 1 to let one commit through with enforcement on
+    GITHUB_TOKEN                            Sent with GitHub API lookups (add --github)
+    GITLAB_TOKEN                            Sent with GitLab API lookups (add --gitlab)
+
+EXAMPLES:
+    git-pair init
+    git-pair add John Doe john.doe@company.com
+    git-pair add --global alice "Alice Johnson" alice@company.com
+    git-pair add alice
+    git-pair add alice --style co-developed
+    git-pair add bob --reviewer
+    git-pair rename --global alice alicej
+    git-pair remove "John Doe"
+    git-pair remove john.doe@company.com
+    git-pair remove alice
+    git-pair status
+    git-pair list --global
+    git-pair list --repos
+    git-pair stats
+    git-pair stats --coauthor alice --path 'services/payments/**'
+    git-pair standup --since "last friday"
+    git-pair rotate
+    git-pair pr-body --template .github/pull_request_template.md
+    git-pair preview -m "Fix login redirect"
+    git-pair roster lint
+    git-pair roster dedupe --dry-run
+    git-pair config trailer-key Pair-programmed-with
+"#,
+        env!("CARGO_PKG_VERSION")
+    )
 }
@@ -0,0 +1,3129 @@
+//! The parts of git-pair that are plain text processing: trailer lines, roster
+//! files, hook sections and pattern matching. Nothing here touches the file system,
+//! runs processes or reads the environment, so this module builds on its own
+//! (`default-features = false`) and for `wasm32-unknown-unknown`, letting web tools
+//! such as a PR-body generator reuse the exact same logic.
+
+// Errors. Every failure is a `GitPairError`, built where it happens: the variant says
+// what went wrong and decides the stable `ErrorCode` the CLI exits with, so nothing
+// has to guess from the wording. The codes and exit statuses are part of the
+// command-line interface: new ones may be added, existing ones never change.
+
+/// A stable, machine-readable classification of a git-pair failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// Anything not covered by a more specific code
+    Error,
+    /// The command line could not be understood
+    Usage,
+    NotARepo,
+    NotInitialized,
+    /// HEAD is detached or the branch name could not be read
+    NoBranch,
+    AliasNotFound,
+    AliasAmbiguous,
+    CoauthorNotFound,
+    /// An alias, co-author or preset is already there
+    AlreadyExists,
+    InvalidValue,
+    /// The branch already has as many co-authors as `max-coauthors` allows
+    MaxCoauthors,
+    /// The command needed the network while offline mode was on
+    Offline,
+    Network,
+    Hook,
+    /// Something the repository's `.git-pair-policy.toml` doesn't allow
+    Policy,
+}
+
+impl ErrorCode {
+    /// Every code, in exit status order
+    pub const ALL: [ErrorCode; 15] = [
+        ErrorCode::Error,
+        ErrorCode::Usage,
+        ErrorCode::NotARepo,
+        ErrorCode::NotInitialized,
+        ErrorCode::NoBranch,
+        ErrorCode::AliasNotFound,
+        ErrorCode::AliasAmbiguous,
+        ErrorCode::CoauthorNotFound,
+        ErrorCode::AlreadyExists,
+        ErrorCode::InvalidValue,
+        ErrorCode::MaxCoauthors,
+        ErrorCode::Offline,
+        ErrorCode::Network,
+        ErrorCode::Hook,
+        ErrorCode::Policy,
+    ];
+
+    /// The name used in `--json` output, such as `NOT_A_REPO`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::Error => "ERROR",
+            ErrorCode::Usage => "USAGE",
+            ErrorCode::NotARepo => "NOT_A_REPO",
+            ErrorCode::NotInitialized => "NOT_INITIALIZED",
+            ErrorCode::NoBranch => "NO_BRANCH",
+            ErrorCode::AliasNotFound => "ALIAS_NOT_FOUND",
+            ErrorCode::AliasAmbiguous => "ALIAS_AMBIGUOUS",
+            ErrorCode::CoauthorNotFound => "COAUTHOR_NOT_FOUND",
+            ErrorCode::AlreadyExists => "ALREADY_EXISTS",
+            ErrorCode::InvalidValue => "INVALID_VALUE",
+            ErrorCode::MaxCoauthors => "MAX_COAUTHORS",
+            ErrorCode::Offline => "OFFLINE",
+            ErrorCode::Network => "NETWORK",
+            ErrorCode::Hook => "HOOK",
+            ErrorCode::Policy => "POLICY",
+        }
+    }
+
+    /// The process exit status for this failure
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorCode::Error => 1,
+            ErrorCode::Usage => 2,
+            ErrorCode::NotARepo => 3,
+            ErrorCode::NotInitialized => 4,
+            ErrorCode::NoBranch => 5,
+            ErrorCode::AliasNotFound => 6,
+            ErrorCode::AliasAmbiguous => 7,
+            ErrorCode::CoauthorNotFound => 8,
+            ErrorCode::AlreadyExists => 9,
+            ErrorCode::InvalidValue => 10,
+            ErrorCode::MaxCoauthors => 11,
+            ErrorCode::Offline => 12,
+            ErrorCode::Network => 13,
+            ErrorCode::Hook => 14,
+            ErrorCode::Policy => 15,
+        }
+    }
+}
+
+/// Why a git-pair operation failed. Variants that callers act on carry the details;
+/// the rest carry their message. [`code`](GitPairError::code) follows from the
+/// variant, and `Display` gives the message without command-line hints such as which
+/// command to run next, which the binary adds. Two `Io` errors are equal when their
+/// action and [`ErrorKind`](std::io::ErrorKind) are.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum GitPairError {
+    /// The command line could not be understood
+    Usage(String),
+    /// The directory isn't in a git repository
+    NotARepo,
+    /// The branch has no git-pair config yet
+    NotInitialized { branch: String },
+    /// HEAD is detached, or the branch name could not be read
+    NoBranch(String),
+    /// An alias that isn't in any roster git-pair looks in
+    AliasNotFound { alias: String },
+    /// An alias prefix that matches several aliases
+    AliasAmbiguous { alias: String, matches: Vec<String> },
+    /// A name, email or alias that matches none of the branch's co-authors
+    CoauthorNotFound { coauthor: String, branch: String },
+    /// An alias, co-author, preset or other entry is already there
+    AlreadyExists(String),
+    /// A value that isn't valid where it was given
+    InvalidValue(String),
+    /// The branch already has as many co-authors as `max-coauthors` allows
+    MaxCoauthors(String),
+    /// Something needed the network while offline mode was on
+    Offline { action: String },
+    /// A request over the network failed
+    Network(String),
+    /// A git hook couldn't be read, written or chained
+    Hook(String),
+    /// Something the repository policy doesn't allow
+    Policy(String),
+    /// A preset, group, roster backup, tool or other named thing that isn't there
+    NotFound(String),
+    /// The branch or repository isn't in a state the command can work from, such as a
+    /// branch without co-authors or a merge in progress
+    InvalidState(String),
+    /// The user answered no, or pressed Ctrl-C or Ctrl-D, at a prompt
+    Cancelled,
+    /// Another git-pair held the lock on `path` for too long
+    LockTimeout { path: String },
+    /// Neither `HOME` nor `USERPROFILE` says where the home directory is
+    NoHomeDir,
+    /// Reading or writing a file, or starting a process, failed while `action`
+    Io {
+        action: String,
+        source: std::sync::Arc<std::io::Error>,
+    },
+    /// Text that should be UTF-8 isn't
+    Utf8 { what: String },
+    /// A git command exited with an error
+    GitCommandFailed { command: String, stderr: String },
+    /// Another program, such as age, qrencode or the editor, exited with an error
+    CommandFailed { command: String, message: String },
+    /// Another error, with what was being done when it happened
+    Context {
+        context: String,
+        source: Box<GitPairError>,
+    },
+}
+
+impl GitPairError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            GitPairError::Usage(_) => ErrorCode::Usage,
+            GitPairError::NotARepo => ErrorCode::NotARepo,
+            GitPairError::NotInitialized { .. } => ErrorCode::NotInitialized,
+            GitPairError::NoBranch(_) => ErrorCode::NoBranch,
+            GitPairError::AliasNotFound { .. } => ErrorCode::AliasNotFound,
+            GitPairError::AliasAmbiguous { .. } => ErrorCode::AliasAmbiguous,
+            GitPairError::CoauthorNotFound { .. } => ErrorCode::CoauthorNotFound,
+            GitPairError::AlreadyExists(_) => ErrorCode::AlreadyExists,
+            GitPairError::InvalidValue(_) => ErrorCode::InvalidValue,
+            GitPairError::MaxCoauthors(_) => ErrorCode::MaxCoauthors,
+            GitPairError::Offline { .. } => ErrorCode::Offline,
+            GitPairError::Network(_) => ErrorCode::Network,
+            GitPairError::Hook(_) => ErrorCode::Hook,
+            GitPairError::Policy(_) => ErrorCode::Policy,
+            GitPairError::NotFound(_)
+            | GitPairError::InvalidState(_)
+            | GitPairError::Cancelled
+            | GitPairError::LockTimeout { .. }
+            | GitPairError::NoHomeDir
+            | GitPairError::Io { .. }
+            | GitPairError::Utf8 { .. }
+            | GitPairError::GitCommandFailed { .. }
+            | GitPairError::CommandFailed { .. } => ErrorCode::Error,
+            GitPairError::Context { source, .. } => source.code(),
+        }
+    }
+
+    /// The error at the bottom of any [`Context`](GitPairError::Context)
+    pub fn root(&self) -> &GitPairError {
+        match self {
+            GitPairError::Context { source, .. } => source.root(),
+            error => error,
+        }
+    }
+
+    /// An I/O failure while `action`, such as `reading settings file`
+    pub fn io(action: impl Into<String>, error: std::io::Error) -> GitPairError {
+        GitPairError::Io {
+            action: action.into(),
+            source: std::sync::Arc::new(error),
+        }
+    }
+
+    /// This error, with what was being done when it happened
+    pub fn context(self, context: impl Into<String>) -> GitPairError {
+        GitPairError::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+}
+
+impl std::fmt::Display for GitPairError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitPairError::NotARepo => f.write_str("Not in a git repository"),
+            GitPairError::NotInitialized { branch } => {
+                write!(f, "git-pair not initialized for branch '{}'", branch)
+            }
+            GitPairError::AliasNotFound { alias } => {
+                write!(f, "Alias '{}' not found in the roster", alias)
+            }
+            GitPairError::AliasAmbiguous { alias, matches } => write!(
+                f,
+                "Alias '{}' is ambiguous, it matches: {}",
+                alias,
+                matches.join(", ")
+            ),
+            GitPairError::CoauthorNotFound { coauthor, branch } => {
+                write!(f, "Co-author '{}' not found on branch '{}'", coauthor, branch)
+            }
+            GitPairError::Offline { action } => write!(
+                f,
+                "{} needs network access, but offline mode is on (--offline, GIT_PAIR_OFFLINE=1 or the offline setting)",
+                action
+            ),
+            GitPairError::Cancelled => f.write_str("Cancelled"),
+            GitPairError::LockTimeout { path } => write!(
+                f,
+                "Timed out waiting for another git-pair to finish with {}",
+                path
+            ),
+            GitPairError::NoHomeDir => f.write_str(
+                "Could not find your home directory: set HOME (or USERPROFILE on Windows)",
+            ),
+            GitPairError::Io { action, source } => write!(f, "Error {}: {}", action, source),
+            GitPairError::Utf8 { what } => write!(f, "{} is not valid UTF-8", what),
+            GitPairError::GitCommandFailed { command, stderr } => {
+                write!(f, "git {} failed: {}", command, stderr)
+            }
+            GitPairError::CommandFailed { command, message } => {
+                write!(f, "{} failed: {}", command, message)
+            }
+            GitPairError::Context { context, source } => write!(f, "{}: {}", context, source),
+            GitPairError::Usage(message)
+            | GitPairError::NoBranch(message)
+            | GitPairError::AlreadyExists(message)
+            | GitPairError::InvalidValue(message)
+            | GitPairError::MaxCoauthors(message)
+            | GitPairError::Network(message)
+            | GitPairError::Hook(message)
+            | GitPairError::Policy(message)
+            | GitPairError::NotFound(message)
+            | GitPairError::InvalidState(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for GitPairError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GitPairError::Context { source, .. } => Some(source.as_ref()),
+            GitPairError::Io { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for GitPairError {
+    fn eq(&self, other: &GitPairError) -> bool {
+        use GitPairError::*;
+        match (self, other) {
+            (Usage(a), Usage(b))
+            | (NoBranch(a), NoBranch(b))
+            | (AlreadyExists(a), AlreadyExists(b))
+            | (InvalidValue(a), InvalidValue(b))
+            | (MaxCoauthors(a), MaxCoauthors(b))
+            | (Network(a), Network(b))
+            | (Hook(a), Hook(b))
+            | (Policy(a), Policy(b))
+            | (NotFound(a), NotFound(b))
+            | (InvalidState(a), InvalidState(b)) => a == b,
+            (NotARepo, NotARepo) | (Cancelled, Cancelled) | (NoHomeDir, NoHomeDir) => true,
+            (NotInitialized { branch: a }, NotInitialized { branch: b }) => a == b,
+            (AliasNotFound { alias: a }, AliasNotFound { alias: b }) => a == b,
+            (
+                AliasAmbiguous { alias, matches },
+                AliasAmbiguous {
+                    alias: other_alias,
+                    matches: other_matches,
+                },
+            ) => alias == other_alias && matches == other_matches,
+            (
+                CoauthorNotFound { coauthor, branch },
+                CoauthorNotFound {
+                    coauthor: other_coauthor,
+                    branch: other_branch,
+                },
+            ) => coauthor == other_coauthor && branch == other_branch,
+            (Offline { action: a }, Offline { action: b }) => a == b,
+            (LockTimeout { path: a }, LockTimeout { path: b }) => a == b,
+            (
+                Io { action, source },
+                Io {
+                    action: other_action,
+                    source: other_source,
+                },
+            ) => action == other_action && source.kind() == other_source.kind(),
+            (Utf8 { what: a }, Utf8 { what: b }) => a == b,
+            (
+                GitCommandFailed { command, stderr },
+                GitCommandFailed {
+                    command: other_command,
+                    stderr: other_stderr,
+                },
+            ) => command == other_command && stderr == other_stderr,
+            (
+                CommandFailed { command, message },
+                CommandFailed {
+                    command: other_command,
+                    message: other_message,
+                },
+            ) => command == other_command && message == other_message,
+            (
+                Context { context, source },
+                Context {
+                    context: other_context,
+                    source: other_source,
+                },
+            ) => context == other_context && source == other_source,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for GitPairError {}
+
+// Trailers. Co-authors appear in commit messages and branch configs as
+// `Key: Name <email>` lines, where the key is usually `Co-authored-by`.
+
+/// A person as they appear in a commit's author or co-author trailers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Coauthor {
+    pub name: String,
+    pub email: String,
+    pub trailer: String,
+    /// Unix timestamp from which the hook leaves the entry out, see `add --for`
+    pub expires: Option<u64>,
+}
+
+impl Coauthor {
+    /// Parses a `Trailer-key: Name <email>` line
+    pub fn from_line(line: &str) -> Option<Coauthor> {
+        let (trailer, identity) = line.split_once(':')?;
+        let identity = identity.trim();
+        let (name, email) = match identity.rsplit_once('<') {
+            Some((name, email)) => (name.trim(), email.trim_end_matches('>').trim()),
+            None => (identity, ""),
+        };
+        Some(Coauthor {
+            name: name.to_string(),
+            email: email.to_string(),
+            trailer: trailer.trim().to_string(),
+            expires: None,
+        })
+    }
+
+    /// Whether the entry's time ran out at `now`, a Unix timestamp
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    /// The trailer line as it appears in commit messages
+    pub fn line(&self) -> String {
+        if self.email.is_empty() {
+            format!("{}: {}", self.trailer, self.name)
+        } else {
+            format!("{}: {} <{}>", self.trailer, self.name, self.email)
+        }
+    }
+}
+
+/// Finds `Key: Name <email>` trailer lines for any of the given keys
+pub fn parse_coauthor_trailers(message: &str, trailer_keys: &[&str]) -> Vec<Identity> {
+    message
+        .lines()
+        .filter_map(Coauthor::from_line)
+        .filter(|coauthor| {
+            !coauthor.email.is_empty()
+                && trailer_keys
+                    .iter()
+                    .any(|key| key.eq_ignore_ascii_case(&coauthor.trailer))
+        })
+        .map(|coauthor| Identity {
+            name: coauthor.name,
+            email: coauthor.email,
+        })
+        .collect()
+}
+
+pub fn matches_coauthor(coauthor_line: &str, identifier: &str) -> bool {
+    // Match by full name (case-insensitive)
+    if coauthor_line
+        .to_lowercase()
+        .contains(&identifier.to_lowercase())
+    {
+        return true;
+    }
+
+    // Match by email
+    if coauthor_line.contains(identifier) {
+        return true;
+    }
+
+    false
+}
+
+// Rosters. A roster file has one `alias|name|email` entry per line; blank lines and
+// lines starting with `#` are ignored. Optional `key=value` fields can follow the
+// email, such as `alice|Alice Johnson|alice@corp.com|pronouns=she/her|timezone=Europe/Berlin`.
+// Fields this version doesn't know are skipped, so rosters written by newer versions
+// still load.
+
+/// A roster entry: alias, name and email
+pub type RosterEntry = (String, String, String);
+
+pub fn parse_roster(content: &str) -> Vec<(String, String, String)> {
+    let mut roster = Vec::new();
+    for line in content.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+
+        let parts: Vec<&str> = line.split('|').collect();
+        if parts.len() >= 3 {
+            roster.push((
+                parts[0].to_string(),
+                parts[1].to_string(),
+                parts[2].to_string(),
+            ));
+        }
+    }
+
+    roster
+}
+
+/// People in a CSV export for `import --csv`, as (alias, name, email): rows of
+/// `name,email` or `alias,name,email`. Fields may be quoted, with `""` for a quote.
+/// A first row without an email is taken as a header; blank lines are skipped.
+pub fn parse_roster_csv(
+    content: &str,
+) -> Result<Vec<(Option<String>, String, String)>, GitPairError> {
+    let mut people = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = split_csv_line(line);
+        if number == 0 && !fields.iter().any(|field| field.contains('@')) {
+            continue;
+        }
+        let person = match fields.as_slice() {
+            [name, email] if email.contains('@') => (None, name.clone(), email.clone()),
+            [alias, name, email] if email.contains('@') => {
+                let alias = Some(alias.clone()).filter(|alias| !alias.is_empty());
+                (alias, name.clone(), email.clone())
+            }
+            _ => {
+                return Err(GitPairError::InvalidValue(format!(
+                    "Line {}: expected name,email or alias,name,email",
+                    number + 1
+                )))
+            }
+        };
+        people.push(person);
+    }
+    Ok(people)
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut().expect("fields is never empty");
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => field.push(c),
+        }
+    }
+    fields
+        .iter()
+        .map(|field| field.trim().to_string())
+        .collect()
+}
+
+/// A CSV field, quoted when it holds a comma, a quote or a line break
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// The optional fields of a roster entry
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RosterMetadata {
+    /// How they like to be called, when it differs from the name in their trailers
+    pub display_name: Option<String>,
+    pub pronouns: Option<String>,
+    /// An IANA zone such as `Europe/Berlin`
+    pub timezone: Option<String>,
+}
+
+impl RosterMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == RosterMetadata::default()
+    }
+}
+
+/// The metadata fields of the first entry for `alias`
+pub fn parse_roster_metadata(content: &str, alias: &str) -> RosterMetadata {
+    let mut metadata = RosterMetadata::default();
+    let Some(line) = content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .find(|line| line.split('|').count() >= 3 && line.split('|').next() == Some(alias))
+    else {
+        return metadata;
+    };
+
+    for field in line.split('|').skip(3) {
+        let Some((key, value)) = field.split_once('=') else {
+            continue;
+        };
+        let value = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+        match key.trim() {
+            "display-name" => metadata.display_name = value,
+            "pronouns" => metadata.pronouns = value,
+            "timezone" => metadata.timezone = value,
+            _ => {}
+        }
+    }
+    metadata
+}
+
+/// The fields after the email of the first entry for `alias`, including `|` in front,
+/// so rewriting the entry doesn't lose them
+pub fn roster_entry_extra_fields(content: &str, alias: &str) -> String {
+    content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .find(|line| line.split('|').count() >= 3 && line.split('|').next() == Some(alias))
+        .map(|line| {
+            line.split('|')
+                .skip(3)
+                .map(|field| format!("|{}", field))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Sets the metadata fields that are `Some` in `extra` (an entry's fields after the
+/// email, as from [`roster_entry_extra_fields`]), keeping every other field as written
+pub fn set_roster_metadata(extra: &str, metadata: &RosterMetadata) -> String {
+    let mut fields: Vec<String> = extra
+        .split('|')
+        .filter(|field| !field.is_empty())
+        .map(str::to_string)
+        .collect();
+    for (key, value) in [
+        ("display-name", &metadata.display_name),
+        ("pronouns", &metadata.pronouns),
+        ("timezone", &metadata.timezone),
+    ] {
+        let Some(value) = value else {
+            continue;
+        };
+        let field = format!("{}={}", key, value.trim());
+        match fields
+            .iter()
+            .position(|f| f.split_once('=').is_some_and(|(k, _)| k.trim() == key))
+        {
+            Some(index) => fields[index] = field,
+            None => fields.push(field),
+        }
+    }
+    fields.iter().map(|field| format!("|{}", field)).collect()
+}
+
+/// The comma-separated `tags=` field of an entry's fields after the email, such as
+/// `team:platform` written by `git pair team sync`
+pub fn roster_entry_tags(extra: &str) -> Vec<String> {
+    extra
+        .split('|')
+        .filter_map(|field| field.split_once('='))
+        .find(|(key, _)| key.trim() == "tags")
+        .map(|(_, tags)| {
+            tags.split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Replaces the `tags=` field in `extra`, or drops it when `tags` is empty, keeping
+/// every other field as written
+pub fn set_roster_entry_tags(extra: &str, tags: &[String]) -> String {
+    let mut fields: Vec<String> = extra
+        .split('|')
+        .filter(|field| !field.is_empty())
+        .map(str::to_string)
+        .collect();
+    let position = fields
+        .iter()
+        .position(|f| f.split_once('=').is_some_and(|(k, _)| k.trim() == "tags"));
+    let field = format!("tags={}", tags.join(","));
+    match (position, tags.is_empty()) {
+        (Some(index), true) => {
+            fields.remove(index);
+        }
+        (Some(index), false) => fields[index] = field,
+        (None, false) => fields.push(field),
+        (None, true) => {}
+    }
+    fields.iter().map(|field| format!("|{}", field)).collect()
+}
+
+/// Normalizes an email for comparison (emails are matched case-insensitively)
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+// A roster entry's email field can list several addresses separated by commas: a
+// default, plus `rule=email` alternatives tried in order. A rule containing `/` (or
+// starting with `~`) matches repositories under that path; any other rule matches the
+// host of one of the repository's remotes, or a subdomain of it:
+//
+//     alice|Alice Johnson|alice@corp.com,github.com=alice@users.noreply.github.com,~/oss=alice@home.dev
+
+/// Splits an email field into the default address and its `(rule, email)` alternatives
+pub fn parse_roster_emails(field: &str) -> (Option<&str>, Vec<(&str, &str)>) {
+    let mut default = None;
+    let mut rules = Vec::new();
+    for part in field.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.split_once('=') {
+            Some((rule, email)) => rules.push((rule.trim(), email.trim())),
+            None if default.is_none() => default = Some(part),
+            None => {}
+        }
+    }
+    (default, rules)
+}
+
+/// The address an entry is known by: its default, or its first alternative
+pub fn primary_email(field: &str) -> &str {
+    let (default, rules) = parse_roster_emails(field);
+    default
+        .or_else(|| rules.first().map(|(_, email)| *email))
+        .unwrap_or("")
+}
+
+/// Every address listed in an email field
+pub fn roster_emails(field: &str) -> Vec<&str> {
+    let (default, rules) = parse_roster_emails(field);
+    default
+        .into_iter()
+        .chain(rules.into_iter().map(|(_, email)| email))
+        .collect()
+}
+
+/// Roster entries outside `archived` whose newest use in `last_used` (normalized email,
+/// unix time) is more than `months` 30-day months before `now`. Entries with no
+/// recorded use are kept.
+pub fn unused_roster_entries(
+    roster: &[RosterEntry],
+    archived: &[String],
+    last_used: &[(String, u64)],
+    now: u64,
+    months: u64,
+) -> Vec<RosterEntry> {
+    let cutoff = now.saturating_sub(months * 30 * 86400);
+    roster
+        .iter()
+        .filter(|(alias, _, _)| !archived.contains(alias))
+        .filter(|(_, _, emails)| {
+            let emails: Vec<String> = roster_emails(emails)
+                .into_iter()
+                .map(normalize_email)
+                .collect();
+            let newest = last_used
+                .iter()
+                .filter(|(email, _)| emails.contains(email))
+                .map(|(_, used)| *used)
+                .max();
+            newest.is_some_and(|used| used < cutoff)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Subcommand names that can't be used as aliases because `git pair add <alias>`
+/// and `git pair remove <alias>` would be ambiguous with them
+pub const RESERVED_ALIASES: &[&str] = &[
+    "init",
+    "onboarding",
+    "add",
+    "remove",
+    "clear",
+    "set-default",
+    "prune",
+    "move",
+    "status",
+    "list",
+    "roster",
+    "rename",
+    "update",
+    "archive",
+    "import",
+    "import-history",
+    "team",
+    "group",
+    "driver",
+    "unarchive",
+    "config",
+    "pause",
+    "solo",
+    "resume",
+    "snooze",
+    "expire",
+    "renew",
+    "adopt",
+    "amend",
+    "squash-msg",
+    "verify",
+    "check",
+    "footer",
+    "enforce",
+    "which",
+    "pin",
+    "diff",
+    "prompt",
+    "message",
+    "once",
+    "commit",
+    "stats",
+    "graph",
+    "standup",
+    "blame",
+    "rotate",
+    "pr-body",
+    "preview",
+    "edit",
+    "auth",
+    "cache",
+    "env",
+    "setup-alias",
+    "completions",
+    "mob",
+    "announce",
+    "share",
+    "apply",
+    "events",
+    "info",
+    "check-config",
+    "doctor",
+    "recent",
+    "save-set",
+    "use",
+    "repair-hook",
+    "upgrade-hooks",
+    "clean-hooks",
+    "install",
+    "install-hooks",
+    "hook",
+    "hook-impl",
+    "help",
+];
+
+pub const MAX_ALIAS_LENGTH: usize = 32;
+
+/// Checks that an alias can be stored in the roster and later matched by `git pair add <alias>`
+pub fn validate_alias(alias: &str) -> Result<(), GitPairError> {
+    if alias.is_empty() {
+        return Err(GitPairError::InvalidValue(
+            "Alias cannot be empty".to_string(),
+        ));
+    }
+
+    if alias.chars().any(char::is_whitespace) {
+        return Err(GitPairError::InvalidValue(format!(
+            "Alias '{}' contains whitespace. Use a single word such as '{}'.",
+            alias,
+            alias.split_whitespace().collect::<Vec<_>>().join("-")
+        )));
+    }
+
+    if alias.contains('|') {
+        return Err(GitPairError::InvalidValue(format!(
+            "Alias '{}' contains '|', which is used as the roster field separator",
+            alias
+        )));
+    }
+
+    if alias.starts_with('-') || alias.starts_with('#') || alias.starts_with('@') {
+        return Err(GitPairError::InvalidValue(format!(
+            "Alias '{}' cannot start with '{}'",
+            alias,
+            &alias[..1]
+        )));
+    }
+
+    if alias.chars().count() > MAX_ALIAS_LENGTH {
+        return Err(GitPairError::InvalidValue(format!(
+            "Alias '{}' is too long ({} characters, maximum is {})",
+            alias,
+            alias.chars().count(),
+            MAX_ALIAS_LENGTH
+        )));
+    }
+
+    if RESERVED_ALIASES.contains(&alias.to_lowercase().as_str()) {
+        return Err(GitPairError::InvalidValue(format!(
+            "Alias '{}' is reserved because it is a git-pair command name",
+            alias
+        )));
+    }
+
+    Ok(())
+}
+
+/// Cleans up a name for a trailer: angle brackets and `|` are dropped, line breaks and
+/// other control characters become spaces, and runs of whitespace are collapsed
+pub fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '<' | '>' | '|'))
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Trims an email and the angle brackets it may be pasted with, as in `<jane@example.com>`
+pub fn sanitize_email(email: &str) -> String {
+    let email = email.trim();
+    email
+        .strip_prefix('<')
+        .and_then(|email| email.strip_suffix('>'))
+        .unwrap_or(email)
+        .trim()
+        .to_string()
+}
+
+/// Checks that an email looks like an address: a local part and a domain around a
+/// single `@`, with no spaces or characters that would break a trailer or the roster
+pub fn validate_email(email: &str) -> Result<(), GitPairError> {
+    let invalid = |reason: &str| {
+        Err(GitPairError::InvalidValue(format!(
+            "Invalid email '{}': {}",
+            email, reason
+        )))
+    };
+    if email.is_empty() {
+        return Err(GitPairError::InvalidValue(
+            "Email cannot be empty".to_string(),
+        ));
+    }
+    if let Some(c) = email
+        .chars()
+        .find(|c| c.is_whitespace() || c.is_control() || "<>()[],;:\\\"|".contains(*c))
+    {
+        return invalid(&format!("it cannot contain {:?}", c));
+    }
+    let Some((local, domain)) = email.split_once('@') else {
+        return invalid("it has no '@'");
+    };
+    if local.is_empty() {
+        return invalid("nothing before the '@'");
+    }
+    if domain.contains('@') {
+        return invalid("more than one '@'");
+    }
+    let bad_dots = |part: &str| part.starts_with('.') || part.ends_with('.') || part.contains("..");
+    if bad_dots(local) {
+        return invalid("misplaced '.' before the '@'");
+    }
+    if domain.is_empty() || bad_dots(domain) {
+        return invalid(&format!("'{}' is not a domain", domain));
+    }
+    Ok(())
+}
+
+pub fn lint_roster(content: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        if line.split('|').count() < 3 {
+            problems.push(format!(
+                "Line {}: malformed entry '{}' (expected alias|name|email)",
+                index + 1,
+                line
+            ));
+        }
+    }
+
+    let roster = parse_roster(content);
+
+    for (alias, _, _) in &roster {
+        if let Err(e) = validate_alias(alias) {
+            problems.push(e.to_string());
+        }
+    }
+
+    let mut seen_aliases: Vec<&str> = Vec::new();
+    for (alias, _, _) in &roster {
+        if seen_aliases.contains(&alias.as_str()) {
+            problems.push(format!("Alias '{}' is defined more than once", alias));
+        } else {
+            seen_aliases.push(alias);
+        }
+    }
+
+    let mut reported_emails: Vec<String> = Vec::new();
+    for (_, _, email) in &roster {
+        let email = primary_email(email);
+        let normalized = normalize_email(email);
+        if reported_emails.contains(&normalized) {
+            continue;
+        }
+
+        let aliases: Vec<&str> = roster
+            .iter()
+            .filter(|(_, _, e)| normalize_email(primary_email(e)) == normalized)
+            .map(|(a, _, _)| a.as_str())
+            .collect();
+        if aliases.len() > 1 {
+            problems.push(format!(
+                "Email <{}> is shared by aliases: {}",
+                email,
+                aliases.join(", ")
+            ));
+        }
+        reported_emails.push(normalized);
+    }
+
+    problems
+}
+
+/// Looks up a roster entry by alias. Exact matches win, then case-insensitive matches,
+/// then a case-insensitive prefix as long as it matches only one alias.
+/// Returns `Ok(None)` when nothing matches and an error when a prefix is ambiguous.
+pub fn find_roster_entry<'a>(
+    roster: &'a [(String, String, String)],
+    query: &str,
+) -> Result<Option<&'a (String, String, String)>, GitPairError> {
+    Ok(match_roster_entry(roster, query)?.map(|(entry, _)| entry))
+}
+
+/// How a query matched a roster alias
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AliasMatch {
+    Exact,
+    CaseInsensitive,
+    Prefix,
+}
+
+impl AliasMatch {
+    pub fn description(&self) -> &'static str {
+        match self {
+            AliasMatch::Exact => "exact alias",
+            AliasMatch::CaseInsensitive => "alias differing only in case",
+            AliasMatch::Prefix => "the only alias starting with the query",
+        }
+    }
+}
+
+/// `find_roster_entry`, also reporting which rule matched
+pub fn match_roster_entry<'a>(
+    roster: &'a [RosterEntry],
+    query: &str,
+) -> Result<Option<(&'a RosterEntry, AliasMatch)>, GitPairError> {
+    if let Some(entry) = roster.iter().find(|(a, _, _)| a == query) {
+        return Ok(Some((entry, AliasMatch::Exact)));
+    }
+
+    let query_lower = query.to_lowercase();
+    if let Some(entry) = roster
+        .iter()
+        .find(|(a, _, _)| a.to_lowercase() == query_lower)
+    {
+        return Ok(Some((entry, AliasMatch::CaseInsensitive)));
+    }
+
+    if query.is_empty() {
+        return Ok(None);
+    }
+
+    let candidates: Vec<&RosterEntry> = roster
+        .iter()
+        .filter(|(a, _, _)| a.to_lowercase().starts_with(&query_lower))
+        .collect();
+
+    match candidates.len() {
+        0 => Ok(None),
+        1 => Ok(Some((candidates[0], AliasMatch::Prefix))),
+        _ => Err(GitPairError::AliasAmbiguous {
+            alias: query.to_string(),
+            matches: candidates.iter().map(|(a, _, _)| a.clone()).collect(),
+        }),
+    }
+}
+
+/// A group of roster entries sharing one email, collapsed into a single alias
+#[derive(Debug, Clone, PartialEq)]
+pub struct RosterMerge {
+    pub email: String,
+    pub kept_alias: String,
+    pub removed_aliases: Vec<String>,
+}
+
+pub fn dedupe_roster(content: &str) -> (String, Vec<RosterMerge>) {
+    let mut merges: Vec<RosterMerge> = Vec::new();
+
+    for (alias, _, email) in parse_roster(content) {
+        let email = primary_email(&email).to_string();
+        let normalized = normalize_email(&email);
+        if let Some(merge) = merges
+            .iter_mut()
+            .find(|m| normalize_email(&m.email) == normalized)
+        {
+            if merge.kept_alias != alias && !merge.removed_aliases.contains(&alias) {
+                merge.removed_aliases.push(alias);
+            }
+        } else {
+            merges.push(RosterMerge {
+                email,
+                kept_alias: alias,
+                removed_aliases: Vec::new(),
+            });
+        }
+    }
+
+    merges.retain(|m| !m.removed_aliases.is_empty());
+
+    let mut new_content = String::new();
+    for line in content.lines() {
+        let alias = line.split('|').next().unwrap_or("");
+        let is_removed = line.split('|').count() >= 3
+            && !line.starts_with('#')
+            && merges
+                .iter()
+                .any(|m| m.removed_aliases.iter().any(|a| a == alias));
+        if !is_removed {
+            new_content.push_str(line);
+            new_content.push('\n');
+        }
+    }
+
+    (new_content, merges)
+}
+
+pub fn rename_roster_alias(content: &str, old_alias: &str, new_alias: &str) -> String {
+    let prefix = format!("{}|", old_alias);
+    let mut new_content = String::new();
+    for line in content.lines() {
+        if let Some(rest) = line.strip_prefix(&prefix) {
+            new_content.push_str(new_alias);
+            new_content.push('|');
+            new_content.push_str(rest);
+        } else {
+            new_content.push_str(line);
+        }
+        new_content.push('\n');
+    }
+    new_content
+}
+
+/// Drops the entry for `alias`, keeping comments and every other line as they are
+pub fn remove_roster_alias(content: &str, alias: &str) -> String {
+    let prefix = format!("{}|", alias);
+    content
+        .lines()
+        .filter(|line| !line.starts_with(&prefix))
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+/// Replaces the name and/or email of the entry for `alias`, keeping the fields after
+/// the email
+pub fn update_roster_entry(
+    content: &str,
+    alias: &str,
+    name: Option<&str>,
+    email: Option<&str>,
+) -> String {
+    let prefix = format!("{}|", alias);
+    let mut new_content = String::new();
+    for line in content.lines() {
+        if line.starts_with(&prefix) {
+            let mut fields: Vec<&str> = line.split('|').collect();
+            if let (Some(name), Some(field)) = (name, fields.get_mut(1)) {
+                *field = name;
+            }
+            if let (Some(email), Some(field)) = (email, fields.get_mut(2)) {
+                *field = email;
+            }
+            new_content.push_str(&fields.join("|"));
+        } else {
+            new_content.push_str(line);
+        }
+        new_content.push('\n');
+    }
+    new_content
+}
+
+/// Where a local roster disagrees with a managed one: an alias the managed roster
+/// defines differently, or a managed person's email under another alias. Returns one
+/// message per difference.
+pub fn roster_drift(managed: &[RosterEntry], local: &[RosterEntry]) -> Vec<String> {
+    let mut drift = Vec::new();
+    for (alias, name, email) in local {
+        if let Some((_, managed_name, managed_email)) = managed.iter().find(|(a, _, _)| a == alias)
+        {
+            if managed_name != name || managed_email != email {
+                drift.push(format!(
+                    "'{}' is {} <{}> in your roster but {} <{}> in the managed roster, which wins",
+                    alias, name, email, managed_name, managed_email
+                ));
+            }
+            continue;
+        }
+        let normalized = normalize_email(primary_email(email));
+        if let Some((managed_alias, _, _)) = managed
+            .iter()
+            .find(|(_, _, e)| normalize_email(primary_email(e)) == normalized)
+        {
+            drift.push(format!(
+                "'{}' in your roster has the email of '{}' in the managed roster",
+                alias, managed_alias
+            ));
+        }
+    }
+    drift
+}
+
+/// What merging one roster into another changed, from `roster sync --from` or
+/// `roster push`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RosterChanges {
+    /// Where the entries came from, or went to
+    pub source: String,
+    pub added: Vec<RosterEntry>,
+    /// Aliases defined differently on both sides where the incoming entry was taken, as
+    /// (before, after)
+    pub updated: Vec<(RosterEntry, RosterEntry)>,
+    /// Aliases defined differently on both sides that were left as they were, as
+    /// (kept, incoming)
+    pub kept: Vec<(RosterEntry, RosterEntry)>,
+    /// Incoming entries left out, with why
+    pub skipped: Vec<(RosterEntry, String)>,
+}
+
+impl RosterChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty()
+    }
+}
+
+/// Merges `incoming` entries into the roster `content`: new aliases are added, and for
+/// an alias both define differently `take_incoming(current, incoming)` decides which
+/// entry stays. Entries only in `content` are kept. Someone whose email is already
+/// there under another alias, or whose alias isn't valid, is skipped. Returns the new
+/// content and what changed.
+pub fn merge_roster(
+    content: &str,
+    incoming: &[RosterEntry],
+    mut take_incoming: impl FnMut(&RosterEntry, &RosterEntry) -> Result<bool, GitPairError>,
+) -> Result<(String, RosterChanges), GitPairError> {
+    let mut content = content.to_string();
+    let mut changes = RosterChanges::default();
+    for entry in incoming {
+        let (alias, name, email) = entry;
+        let current = parse_roster(&content);
+        if let Some(existing) = current.iter().find(|(a, _, _)| a == alias) {
+            if existing == entry {
+                continue;
+            }
+            if take_incoming(existing, entry)? {
+                content = update_roster_entry(&content, alias, Some(name), Some(email));
+                changes.updated.push((existing.clone(), entry.clone()));
+            } else {
+                changes.kept.push((existing.clone(), entry.clone()));
+            }
+            continue;
+        }
+        if let Err(e) = validate_alias(alias) {
+            changes.skipped.push((entry.clone(), e.to_string()));
+            continue;
+        }
+        let normalized = normalize_email(primary_email(email));
+        if let Some((other, _, _)) = current
+            .iter()
+            .find(|(_, _, e)| normalize_email(primary_email(e)) == normalized)
+        {
+            changes
+                .skipped
+                .push((entry.clone(), format!("<{}> is already '{}'", email, other)));
+            continue;
+        }
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&format!("{}|{}|{}\n", alias, name, email));
+        changes.added.push(entry.clone());
+    }
+    Ok((content, changes))
+}
+
+/// The value of a `# key=value` header line in a roster
+pub fn roster_header(content: &str, key: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let (k, value) = line.strip_prefix('#')?.trim().split_once('=')?;
+        (k.trim() == key).then(|| value.trim().to_string())
+    })
+}
+
+/// Sets a `# key=value` header line in a roster, replacing the existing one or adding it
+/// after the leading comments
+pub fn set_roster_header(content: &str, key: &str, value: &str) -> String {
+    let header = format!("# {}={}", key, value);
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let existing = lines.iter().position(|line| {
+        line.strip_prefix('#')
+            .and_then(|line| line.trim().split_once('='))
+            .is_some_and(|(k, _)| k.trim() == key)
+    });
+    match existing {
+        Some(index) => lines[index] = header,
+        None => {
+            let index = lines
+                .iter()
+                .position(|line| !line.starts_with('#'))
+                .unwrap_or(lines.len());
+            lines.insert(index, header);
+        }
+    }
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+// Footers. A branch can carry an extra block the hook adds next to the co-author
+// trailers, such as `Refs: {ticket}` or `Pairing-session: <link>`. `{branch}` is
+// replaced with the branch name and `{ticket}` with the first issue key in it.
+
+/// The first issue key like `PAY-123` in a branch name, as `feature/PAY-123-refunds`
+pub fn branch_ticket(branch: &str) -> Option<&str> {
+    let bytes = branch.as_bytes();
+    for start in 0..bytes.len() {
+        if !bytes[start].is_ascii_uppercase() {
+            continue;
+        }
+        let key_end = start
+            + 1
+            + bytes[start + 1..]
+                .iter()
+                .take_while(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+                .count();
+        if key_end == start + 1 || bytes.get(key_end) != Some(&b'-') {
+            continue;
+        }
+        let digits = bytes[key_end + 1..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if digits > 0 {
+            return Some(&branch[start..key_end + 1 + digits]);
+        }
+    }
+    None
+}
+
+/// Fills in a footer template for `branch`
+pub fn render_footer(template: &str, branch: &str) -> String {
+    template
+        .replace("{branch}", branch)
+        .replace("{ticket}", branch_ticket(branch).unwrap_or_default())
+}
+
+// Header templates. The `config-header` and `hook-header` settings replace the
+// description comment at the top of branch configs and of the prepare-commit-msg hook
+// section, e.g. with a link to the team's pairing guidelines. `\n` starts a new line.
+
+/// Renders a header template as comment lines, replacing each `{name}` in `variables`.
+/// Lines that don't start with `#` get a `# ` prefix.
+pub fn render_header_template(template: &str, variables: &[(&str, &str)]) -> String {
+    let mut text = template.replace("\\n", "\n");
+    for (name, value) in variables {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text.lines()
+        .map(|line| {
+            if line.starts_with('#') {
+                line.to_string()
+            } else if line.trim().is_empty() {
+                "#".to_string()
+            } else {
+                format!("# {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Trailer placement. The hook puts its block where `git interpret-trailers` looks for
+// trailers: after the message, separated from it by exactly one blank line, or joined
+// to the message's own trailer paragraph (a `Signed-off-by` from `-s`, say). The
+// comments git adds for the editor, and everything from the `--verbose` scissors line
+// on, stay after the block, since git strips them.
+
+/// The line `git commit --verbose` puts above the diff, after the comment character
+pub const SCISSORS_LINE: &str = " ------------------------ >8 ------------------------";
+
+/// Whether a line reads as a trailer (`Token: value`) or a continuation of one
+fn is_trailer_line(line: &str) -> bool {
+    if line.starts_with([' ', '\t']) {
+        return true;
+    }
+    match line.split_once(": ") {
+        Some((token, _)) => is_trailer_token(token),
+        None => false,
+    }
+}
+
+/// Whether a trailer key is letters, digits and dashes, not starting with a dash
+pub(crate) fn is_trailer_token(token: &str) -> bool {
+    !token.is_empty()
+        && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && !token.starts_with('-')
+}
+
+/// Adds a block of trailer lines to a commit message. Lines starting with
+/// `comment_char` at the end of the message (and the scissors section) are kept after
+/// it.
+pub fn insert_trailer_block(message: &str, block: &str, comment_char: char) -> String {
+    let lines: Vec<&str> = message.lines().collect();
+    let is_comment = |line: &str| line.starts_with(comment_char);
+    let scissors = format!("{}{}", comment_char, SCISSORS_LINE);
+    let cut = lines
+        .iter()
+        .position(|line| *line == scissors)
+        .unwrap_or(lines.len());
+    let mut body_end = cut;
+    while body_end > 0 && (lines[body_end - 1].trim().is_empty() || is_comment(lines[body_end - 1]))
+    {
+        body_end -= 1;
+    }
+    let tail: Vec<&str> = lines[body_end..]
+        .iter()
+        .skip_while(|line| line.trim().is_empty())
+        .copied()
+        .collect();
+    let body = &lines[..body_end];
+
+    // The last paragraph is the message's trailer block unless it's the only one
+    let paragraph_start = body
+        .iter()
+        .rposition(|line| line.trim().is_empty())
+        .map(|blank| blank + 1);
+    let joins_trailers = paragraph_start.is_some_and(|start| {
+        body[start..].iter().all(|line| is_trailer_line(line))
+            && !body[start].starts_with([' ', '\t'])
+    });
+
+    let mut result = String::new();
+    for line in body {
+        result.push_str(line);
+        result.push('\n');
+    }
+    if !joins_trailers {
+        // An empty message keeps its first line free for the subject
+        result.push('\n');
+        if body.is_empty() {
+            result.push('\n');
+        }
+    }
+    for line in block.lines() {
+        result.push_str(line);
+        result.push('\n');
+    }
+    if !tail.is_empty() {
+        result.push('\n');
+        for line in tail {
+            result.push_str(line);
+            result.push('\n');
+        }
+    }
+    result
+}
+
+/// Encodings git can be told to write commit messages in (`i18n.commitEncoding`)
+/// that git-pair can transcode trailers to. The single-byte ones map every byte to a
+/// character, so a message decodes and encodes back unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageEncoding {
+    Utf8,
+    /// ISO-8859-1
+    Latin1,
+    /// ISO-8859-15, Latin-1 with the euro sign and a few more letters
+    Latin9,
+    Windows1252,
+}
+
+/// Characters of Windows-1252 bytes 0x80-0x9F; the five bytes it leaves undefined
+/// stand for the control characters of the same number, as in Latin-1
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}', '\u{8F}',
+    '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
+
+/// The bytes where ISO-8859-15 differs from Latin-1, and their characters
+const LATIN9_CHANGES: [(u8, char); 8] = [
+    (0xA4, '\u{20AC}'),
+    (0xA6, '\u{160}'),
+    (0xA8, '\u{161}'),
+    (0xB4, '\u{17D}'),
+    (0xB8, '\u{17E}'),
+    (0xBC, '\u{152}'),
+    (0xBD, '\u{153}'),
+    (0xBE, '\u{178}'),
+];
+
+impl MessageEncoding {
+    /// Parses an encoding name as git and iconv spell it (`ISO-8859-1`, `latin1`,
+    /// `cp1252`, ...), ignoring case, dashes and underscores
+    pub fn parse(name: &str) -> Option<MessageEncoding> {
+        let name: String = name
+            .chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .collect::<String>()
+            .to_ascii_lowercase();
+        match name.as_str() {
+            "utf8" => Some(MessageEncoding::Utf8),
+            "iso88591" | "latin1" | "l1" => Some(MessageEncoding::Latin1),
+            "iso885915" | "latin9" | "l9" => Some(MessageEncoding::Latin9),
+            "windows1252" | "cp1252" => Some(MessageEncoding::Windows1252),
+            _ => None,
+        }
+    }
+
+    /// Decodes a commit message; fails only for UTF-8 that isn't valid
+    pub fn decode(self, bytes: &[u8]) -> Result<String, GitPairError> {
+        if self == MessageEncoding::Utf8 {
+            return String::from_utf8(bytes.to_vec()).map_err(|_| GitPairError::Utf8 {
+                what: "The commit message".to_string(),
+            });
+        }
+        Ok(bytes.iter().map(|byte| self.char_for(*byte)).collect())
+    }
+
+    /// Encodes a commit message. Characters the encoding has no byte for become `?`,
+    /// and the second value says whether there were any.
+    pub fn encode(self, text: &str) -> (Vec<u8>, bool) {
+        if self == MessageEncoding::Utf8 {
+            return (text.as_bytes().to_vec(), false);
+        }
+        let mut lossy = false;
+        let bytes = text
+            .chars()
+            .map(|c| {
+                self.byte_for(c).unwrap_or_else(|| {
+                    lossy = true;
+                    b'?'
+                })
+            })
+            .collect();
+        (bytes, lossy)
+    }
+
+    /// The character of a byte in one of the single-byte encodings
+    fn char_for(self, byte: u8) -> char {
+        match (self, byte) {
+            (MessageEncoding::Latin9, _) => LATIN9_CHANGES
+                .iter()
+                .find(|(b, _)| *b == byte)
+                .map_or(byte as char, |(_, c)| *c),
+            (MessageEncoding::Windows1252, 0x80..=0x9F) => {
+                WINDOWS_1252_HIGH[(byte - 0x80) as usize]
+            }
+            _ => byte as char,
+        }
+    }
+
+    /// The byte for a character in one of the single-byte encodings, if it has one
+    fn byte_for(self, c: char) -> Option<u8> {
+        let latin1 = u8::try_from(c as u32).ok();
+        match self {
+            MessageEncoding::Latin9 => match LATIN9_CHANGES.iter().find(|(_, ch)| *ch == c) {
+                Some((b, _)) => Some(*b),
+                None => latin1.filter(|b| !LATIN9_CHANGES.iter().any(|(changed, _)| changed == b)),
+            },
+            MessageEncoding::Windows1252 => {
+                match WINDOWS_1252_HIGH.iter().position(|ch| *ch == c) {
+                    Some(i) => Some(0x80 + i as u8),
+                    None => latin1.filter(|b| !(0x80..=0x9F).contains(b)),
+                }
+            }
+            MessageEncoding::Utf8 | MessageEncoding::Latin1 => latin1,
+        }
+    }
+}
+
+/// A line-by-line diff of two messages, each line marked `' '` if both have it, `'-'`
+/// if only `before` does or `'+'` if only `after` does
+pub fn diff_lines<'a>(before: &'a str, after: &'a str) -> Vec<(char, &'a str)> {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+
+    // Longest common subsequence lengths of every pair of suffixes
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            diff.push(('-', old[i]));
+            i += 1;
+        } else {
+            diff.push(('+', new[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+/// `value` as a JSON string literal
+pub fn json_string(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Share snippets. `share` prints a branch's pairing as `git-pair:1:<data>`, where <data>
+// is unpadded URL-safe base64 of lines like these, so it survives chat clients, URLs
+// and QR codes:
+//
+//     Jane Doe<TAB>jane@example.com<TAB>cd,driver    a person, with optional flags
+//     <TAB>trailer-order<TAB>rotate                  a setting (no name)
+
+/// Marks a share snippet; the format version and the data follow
+pub const SNIPPET_PREFIX: &str = "git-pair:";
+
+const SNIPPET_VERSION: u32 = 1;
+
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// A person carried by a share snippet
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SharedCoauthor {
+    pub name: String,
+    pub email: String,
+    /// Credited with `Co-developed-by` and `Signed-off-by` rather than the trailer key
+    pub co_developed: bool,
+    /// The person who shared the pairing. They're a co-author for everyone else.
+    pub driver: bool,
+}
+
+/// A pairing carried by a share snippet
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PairSnippet {
+    pub coauthors: Vec<SharedCoauthor>,
+    /// Repository settings as `(key, value)`
+    pub settings: Vec<(String, String)>,
+}
+
+/// Encodes `snippet` as a share snippet
+pub fn encode_snippet(snippet: &PairSnippet) -> String {
+    let mut data: Vec<String> = snippet
+        .coauthors
+        .iter()
+        .map(|person| {
+            let mut line = format!("{}\t{}", person.name, person.email);
+            let flags: Vec<&str> = [(person.co_developed, "cd"), (person.driver, "driver")]
+                .iter()
+                .filter(|(set, _)| *set)
+                .map(|(_, flag)| *flag)
+                .collect();
+            if !flags.is_empty() {
+                line.push_str(&format!("\t{}", flags.join(",")));
+            }
+            line
+        })
+        .collect();
+    data.extend(
+        snippet
+            .settings
+            .iter()
+            .map(|(key, value)| format!("\t{}\t{}", key, value)),
+    );
+
+    let bytes = data.join("\n").into_bytes();
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let value = chunk.iter().enumerate().fold(0u32, |value, (i, byte)| {
+            value | (*byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..=chunk.len() {
+            encoded.push(BASE64_URL[(value >> (18 - 6 * i) & 63) as usize] as char);
+        }
+    }
+    format!("{}{}:{}", SNIPPET_PREFIX, SNIPPET_VERSION, encoded)
+}
+
+/// Decodes a share snippet. Text around it, such as a URL or a chat message, is
+/// ignored, and so are flags this version doesn't know.
+pub fn decode_snippet(text: &str) -> Result<PairSnippet, GitPairError> {
+    let invalid =
+        |problem: &str| GitPairError::InvalidValue(format!("Invalid share snippet: {}", problem));
+    let start = text
+        .find(SNIPPET_PREFIX)
+        .ok_or_else(|| invalid(&format!("expected '{}...'", SNIPPET_PREFIX)))?;
+    let snippet = text[start + SNIPPET_PREFIX.len()..]
+        .split(|c: char| c.is_whitespace() || c == '&' || c == '#')
+        .next()
+        .unwrap_or_default();
+    let (version, data) = snippet
+        .split_once(':')
+        .ok_or_else(|| invalid("no format version"))?;
+    match version.parse::<u32>() {
+        Ok(SNIPPET_VERSION) => {}
+        Ok(version) if version > SNIPPET_VERSION => {
+            return Err(GitPairError::InvalidValue(format!(
+                "This share snippet uses format {}, which needs a newer git-pair",
+                version
+            )))
+        }
+        _ => return Err(invalid(&format!("unknown format '{}'", version))),
+    }
+
+    let mut bytes = Vec::new();
+    let (mut value, mut bits) = (0u32, 0);
+    for c in data.bytes() {
+        let digit = BASE64_URL
+            .iter()
+            .position(|d| *d == c)
+            .ok_or_else(|| invalid(&format!("unexpected character '{}'", c as char)))?;
+        value = value << 6 | digit as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((value >> bits) as u8);
+            value &= (1 << bits) - 1;
+        }
+    }
+    let data = String::from_utf8(bytes).map_err(|_| invalid("the data is not UTF-8"))?;
+
+    let mut snippet = PairSnippet::default();
+    for line in data.lines() {
+        if line.chars().any(|c| c.is_control() && c != '\t') {
+            return Err(invalid(&format!("bad line '{}'", line.escape_debug())));
+        }
+        let mut fields = line.split('\t');
+        let name = fields.next().unwrap_or_default().trim();
+        let second = fields.next().unwrap_or_default().trim();
+        let third = fields.next().unwrap_or_default().trim();
+        if name.is_empty() {
+            if second.is_empty() {
+                return Err(invalid(&format!("bad setting '{}'", line.trim())));
+            }
+            snippet
+                .settings
+                .push((second.to_string(), third.to_string()));
+            continue;
+        }
+        if !second.contains('@') {
+            return Err(invalid(&format!("bad co-author '{}'", line)));
+        }
+        let flags: Vec<&str> = third.split(',').map(str::trim).collect();
+        snippet.coauthors.push(SharedCoauthor {
+            name: name.to_string(),
+            email: second.to_string(),
+            co_developed: flags.contains(&"cd"),
+            driver: flags.contains(&"driver"),
+        });
+    }
+    if snippet.coauthors.is_empty() {
+        return Err(invalid("it has no co-authors"));
+    }
+    Ok(snippet)
+}
+
+// Hook sections. git-pair owns only the part of a hook between `# BEGIN git-pair` and
+// `# END git-pair`, so it can share the file with other tools.
+
+/// Converts CRLF to LF. Git for Windows runs hooks through sh, where a `\r` left on the
+/// shebang or any other line makes the hook fail.
+pub fn normalize_line_endings(content: &str) -> String {
+    content.replace("\r\n", "\n")
+}
+
+/// Merges git-pair section into existing hook content
+pub fn merge_git_pair_section(
+    existing_content: &str,
+    git_pair_section: &str,
+) -> Result<String, GitPairError> {
+    let existing_content = &normalize_line_endings(existing_content);
+    let git_pair_section = &normalize_line_endings(git_pair_section);
+
+    const BEGIN_MARKER: &str = "# BEGIN git-pair";
+    const END_MARKER: &str = "# END git-pair";
+
+    // Check if git-pair section already exists
+    if let Some(begin_pos) = existing_content.find(BEGIN_MARKER) {
+        if let Some(end_pos) = existing_content.find(END_MARKER) {
+            // Replace existing git-pair section
+            let before = &existing_content[..begin_pos];
+            let after = &existing_content[end_pos + END_MARKER.len()..];
+
+            // Remove trailing newline from 'before' if it exists, and ensure proper spacing
+            let before_trimmed = before.trim_end();
+            let after_trimmed = after.trim_start();
+
+            let mut result = String::new();
+
+            // Add shebang if this is a new file and before section is empty
+            if before_trimmed.is_empty() && !existing_content.starts_with("#!") {
+                result.push_str("#!/bin/sh\n");
+            }
+
+            if !before_trimmed.is_empty() {
+                result.push_str(before_trimmed);
+                result.push('\n');
+            }
+
+            result.push_str(git_pair_section);
+
+            if !after_trimmed.is_empty() {
+                result.push('\n');
+                result.push_str(after_trimmed);
+            }
+
+            Ok(result)
+        } else {
+            Err(GitPairError::Hook(
+                "Found BEGIN marker but no END marker in existing hook".to_string(),
+            ))
+        }
+    } else {
+        // Append git-pair section to existing content
+        let mut result = String::new();
+
+        if existing_content.trim().is_empty() {
+            // New file, add shebang
+            result.push_str("#!/bin/sh\n");
+            result.push_str(git_pair_section);
+        } else {
+            // Existing content, append our section
+            result.push_str(existing_content.trim_end());
+            result.push_str("\n\n");
+            result.push_str(git_pair_section);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Removes git-pair section from hook content, returns None if no section found
+pub fn remove_git_pair_section(content: &str) -> Option<String> {
+    const BEGIN_MARKER: &str = "# BEGIN git-pair";
+    const END_MARKER: &str = "# END git-pair";
+
+    if let Some(begin_pos) = content.find(BEGIN_MARKER) {
+        if let Some(end_pos) = content.find(END_MARKER) {
+            let before = &content[..begin_pos];
+            let after = &content[end_pos + END_MARKER.len()..];
+
+            // Clean up spacing - remove extra newlines around the removed section
+            let before_trimmed = before.trim_end();
+            let after_trimmed = after.trim_start();
+
+            let mut result = String::new();
+
+            if !before_trimmed.is_empty() {
+                result.push_str(before_trimmed);
+                if !after_trimmed.is_empty() {
+                    result.push('\n');
+                }
+            }
+
+            if !after_trimmed.is_empty() {
+                result.push_str(after_trimmed);
+            }
+
+            Some(result)
+        } else {
+            // Found BEGIN but no END, don't modify
+            None
+        }
+    } else {
+        // No git-pair section found
+        None
+    }
+}
+
+/// Returns the installed git-pair section (markers included), if any
+pub fn extract_git_pair_section(content: &str) -> Option<&str> {
+    const BEGIN_MARKER: &str = "# BEGIN git-pair";
+    const END_MARKER: &str = "# END git-pair";
+
+    let begin_pos = content.find(BEGIN_MARKER)?;
+    let end_pos = content.find(END_MARKER)?;
+    if end_pos < begin_pos {
+        return None;
+    }
+    Some(&content[begin_pos..end_pos + END_MARKER.len()])
+}
+
+/// Shell-style match where `*` is any run of characters (including `/`) and `?` is
+/// any single character
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Groups commit timestamps (in seconds, any order) into sessions, starting a new
+/// session whenever more than `gap` seconds pass between commits, and returns how
+/// long each session lasted. `lead` is added to every session for the work done
+/// before its first commit.
+pub fn session_durations(timestamps: &[u64], gap: u64, lead: u64) -> Vec<u64> {
+    let mut sorted = timestamps.to_vec();
+    sorted.sort_unstable();
+
+    let mut durations = Vec::new();
+    let mut iter = sorted.into_iter();
+    let Some(mut start) = iter.next() else {
+        return durations;
+    };
+    let mut last = start;
+    for timestamp in iter {
+        if timestamp - last > gap {
+            durations.push(last - start + lead);
+            start = timestamp;
+        }
+        last = timestamp;
+    }
+    durations.push(last - start + lead);
+    durations
+}
+
+/// Paired and solo commits on one day
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DayActivity {
+    /// Days since the Unix epoch (UTC)
+    pub day: i64,
+    pub paired: usize,
+    pub solo: usize,
+}
+
+/// The current and longest runs of consecutive days with at least one paired commit.
+/// The current streak survives a day that hasn't had a paired commit yet, so it
+/// counts up to yesterday until `today` is paired.
+pub fn pairing_streaks(activity: &[DayActivity], today: i64) -> (usize, usize) {
+    let mut days: Vec<i64> = activity
+        .iter()
+        .filter(|a| a.paired > 0)
+        .map(|a| a.day)
+        .collect();
+    days.sort_unstable();
+    days.dedup();
+
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous = None;
+    for &day in &days {
+        run = if previous == Some(day - 1) {
+            run + 1
+        } else {
+            1
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+    let current = match previous {
+        Some(last) if last == today || last == today - 1 => run,
+        _ => 0,
+    };
+    (current, longest)
+}
+
+/// Cells of a pairing heatmap: no commits, only solo commits, some paired, all paired
+pub const HEATMAP_CELLS: [char; 4] = ['·', '░', '▒', '█'];
+
+/// Renders `activity` as a calendar, one column per week (Monday first) and one row
+/// per weekday, ending with the week of `today`. It starts at the week of the first
+/// commit, or `max_weeks` before today if that is later.
+pub fn render_heatmap(activity: &[DayActivity], today: i64, max_weeks: usize) -> String {
+    // 1970-01-01 was a Thursday
+    let weekday = |day: i64| (day + 3).rem_euclid(7);
+    let last_monday = today - weekday(today);
+    let oldest = activity.iter().map(|a| a.day).min().unwrap_or(today);
+    let weeks = ((last_monday - (oldest - weekday(oldest))) / 7 + 1).clamp(1, max_weeks as i64);
+    let first_monday = last_monday - (weeks - 1) * 7;
+
+    let mut chart = String::new();
+    for (row, label) in ["Mon", "", "Wed", "", "Fri", "", "Sun"].iter().enumerate() {
+        let mut line = format!("{:<4}", label);
+        for week in 0..weeks {
+            let day = first_monday + week * 7 + row as i64;
+            let cell = match activity.iter().find(|a| a.day == day) {
+                _ if day > today => ' ',
+                None => HEATMAP_CELLS[0],
+                Some(a) if a.paired == 0 => HEATMAP_CELLS[1],
+                Some(a) if a.solo > 0 => HEATMAP_CELLS[2],
+                Some(_) => HEATMAP_CELLS[3],
+            };
+            line.push(cell);
+        }
+        chart.push_str(line.trim_end());
+        chart.push('\n');
+    }
+    chart
+}
+
+// Shell completion. `git-pair completions <shell>` prints a script generated from the
+// binary's command table. Roster aliases are looked up when Tab is pressed, through
+// `git-pair completions --aliases`, so the script doesn't go stale as the roster changes.
+
+/// The shells `git-pair completions` writes scripts for
+pub const COMPLETION_SHELLS: &[&str] = &["bash", "zsh", "fish", "powershell"];
+
+/// A command as the command line parser and completion see it
+#[derive(Debug, Clone, Copy)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    /// Words that can follow the command, like `lint` after `roster`
+    pub subcommands: &'static [&'static str],
+    /// The options it understands, such as `--dry-run`
+    pub options: &'static [&'static str],
+    /// Whether it takes arguments besides its subcommands and options
+    pub arguments: bool,
+    /// Whether roster aliases are completed as its arguments
+    pub aliases: bool,
+}
+
+impl CommandSpec {
+    /// The first problem with the arguments given to the command: an option it doesn't
+    /// know, or an argument to a command that takes none. `--option=value` is checked
+    /// by its name.
+    pub fn check_arguments(&self, args: &[String]) -> Option<String> {
+        args.iter().find_map(|arg| {
+            if arg.starts_with("--") {
+                let option = arg.split('=').next().unwrap_or(arg);
+                (!self.options.contains(&option))
+                    .then(|| format!("Unknown option '{}' for 'git-pair {}'", option, self.name))
+            } else if arg.starts_with('-')
+                || self.arguments
+                || self.subcommands.contains(&arg.as_str())
+            {
+                None
+            } else {
+                Some(format!(
+                    "'git-pair {}' takes no arguments, got '{}'",
+                    self.name, arg
+                ))
+            }
+        })
+    }
+
+    /// Everything completed after the command
+    fn words(&self) -> impl Iterator<Item = &'static str> {
+        self.subcommands.iter().chain(self.options).copied()
+    }
+}
+
+/// The completion script for `shell`, one of [`COMPLETION_SHELLS`]
+pub fn completion_script(shell: &str, commands: &[CommandSpec]) -> Result<String, GitPairError> {
+    let names = commands.iter().map(|c| c.name).collect::<Vec<_>>();
+    let alias_commands = commands
+        .iter()
+        .filter(|c| c.aliases)
+        .map(|c| c.name)
+        .collect::<Vec<_>>();
+    let script = match shell {
+        "bash" => {
+            let cases = commands
+                .iter()
+                .filter(|c| c.words().next().is_some())
+                .map(|c| {
+                    format!(
+                        "            {}) words=\"{}\" ;;\n",
+                        c.name,
+                        c.words().collect::<Vec<_>>().join(" ")
+                    )
+                })
+                .collect::<String>();
+            format!(
+                r#"# bash completion for git-pair, from 'git-pair completions bash'
+_git_pair_complete() {{
+    local cur=${{COMP_WORDS[COMP_CWORD]}} start=1 words= i
+    # As 'git pair', the command follows the word 'pair'
+    if [[ ${{COMP_WORDS[0]}} == git ]]; then
+        for ((i = 1; i < COMP_CWORD; i++)); do
+            if [[ ${{COMP_WORDS[i]}} == pair ]]; then
+                start=$((i + 1))
+                break
+            fi
+        done
+    fi
+    if ((COMP_CWORD <= start)); then
+        words="{names}"
+    else
+        case ${{COMP_WORDS[start]}} in
+{cases}        esac
+        case ${{COMP_WORDS[start]}} in
+            {alias_commands})
+                [[ $cur == -* ]] || words="$words $(git-pair completions --aliases 2>/dev/null)"
+                ;;
+        esac
+    fi
+    COMPREPLY=($(compgen -W "$words" -- "$cur"))
+}}
+
+# Called by git's own completion for 'git pair'
+_git_pair() {{
+    _git_pair_complete
+}}
+
+complete -F _git_pair_complete git-pair
+"#,
+                names = names.join(" "),
+                cases = cases,
+                alias_commands = alias_commands.join("|"),
+            )
+        }
+        "zsh" => {
+            let cases = commands
+                .iter()
+                .filter(|c| c.words().next().is_some())
+                .map(|c| {
+                    format!(
+                        "        {}) compadd -- {} ;;\n",
+                        c.name,
+                        c.words().collect::<Vec<_>>().join(" ")
+                    )
+                })
+                .collect::<String>();
+            format!(
+                r#"#compdef git-pair
+# zsh completion for git-pair, from 'git-pair completions zsh'
+
+# As 'git-pair' or, through git's own completion, 'git pair', the command is word 2
+_git-pair() {{
+    if ((CURRENT == 2)); then
+        compadd -- {names}
+        return
+    fi
+    case $words[2] in
+{cases}    esac
+    case $words[2] in
+        {alias_commands})
+            [[ $PREFIX == -* ]] || compadd -- ${{(f)"$(git-pair completions --aliases 2>/dev/null)"}}
+            ;;
+    esac
+}}
+
+if [[ $zsh_eval_context[-1] == loadautofunc ]]; then
+    _git-pair "$@"
+else
+    compdef _git-pair git-pair
+fi
+"#,
+                names = names.join(" "),
+                cases = cases,
+                alias_commands = alias_commands.join("|"),
+            )
+        }
+        "fish" => {
+            let mut script = format!(
+                "# fish completion for git-pair, from 'git-pair completions fish'\n\
+                 complete -c git-pair -f\n\
+                 complete -c git-pair -n __fish_use_subcommand -a '{}'\n",
+                names.join(" ")
+            );
+            for command in commands {
+                let seen = format!("'__fish_seen_subcommand_from {}'", command.name);
+                if !command.subcommands.is_empty() {
+                    script.push_str(&format!(
+                        "complete -c git-pair -n {} -a '{}'\n",
+                        seen,
+                        command.subcommands.join(" ")
+                    ));
+                }
+                for option in command.options {
+                    script.push_str(&format!(
+                        "complete -c git-pair -n {} -l {}\n",
+                        seen,
+                        option.trim_start_matches('-')
+                    ));
+                }
+            }
+            script.push_str(&format!(
+                "complete -c git-pair -n '__fish_seen_subcommand_from {}' -a '(git-pair completions --aliases 2>/dev/null)'\n",
+                alias_commands.join(" ")
+            ));
+            script
+        }
+        "powershell" => {
+            let quote = |words: Vec<&str>| {
+                words
+                    .iter()
+                    .map(|word| format!("'{}'", word))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            let table = commands
+                .iter()
+                .map(|c| format!("        '{}' = @({})\n", c.name, quote(c.words().collect())))
+                .collect::<String>();
+            format!(
+                r#"# PowerShell completion for git-pair, from 'git-pair completions powershell'
+Register-ArgumentCompleter -Native -CommandName 'git-pair' -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+    $commands = [ordered]@{{
+{table}    }}
+    $aliasCommands = @({alias_commands})
+    $elements = @($commandAst.CommandElements | Select-Object -Skip 1 | ForEach-Object {{ $_.ToString() }})
+    if ($wordToComplete) {{
+        $elements = @($elements | Select-Object -SkipLast 1)
+    }}
+    if ($elements.Count -eq 0) {{
+        $candidates = @($commands.Keys)
+    }} else {{
+        $candidates = @($commands[$elements[0]])
+        if ($aliasCommands -contains $elements[0] -and -not $wordToComplete.StartsWith('-')) {{
+            $candidates += @(git-pair completions --aliases 2>$null)
+        }}
+    }}
+    $candidates | Where-Object {{ $_ -like "$wordToComplete*" }} | ForEach-Object {{
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }}
+}}
+"#,
+                table = table,
+                alias_commands = quote(alias_commands),
+            )
+        }
+        _ => {
+            return Err(GitPairError::InvalidValue(format!(
+                "Unknown shell '{}', expected one of: {}",
+                shell,
+                COMPLETION_SHELLS.join(", ")
+            )))
+        }
+    };
+    Ok(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches(
+            "feature/payments-*",
+            "feature/payments-refunds"
+        ));
+        assert!(glob_matches("feature/payments-*", "feature/payments-"));
+        assert!(!glob_matches("feature/payments-*", "feature/pay"));
+        assert!(glob_matches("*/login", "bugfix/auth/login"));
+        assert!(glob_matches("release-?.x", "release-2.x"));
+        assert!(!glob_matches("release-?.x", "release-10.x"));
+        assert!(glob_matches("*a*b", "xxaybyb"));
+        assert!(!glob_matches("main", "main2"));
+    }
+
+    #[test]
+    fn test_validate_email() {
+        assert!(validate_email("jane@example.com").is_ok());
+        assert!(validate_email("j.doe+pair@mail.example.co.uk").is_ok());
+        assert!(validate_email("jane@localhost").is_ok());
+        assert!(validate_email("zoe@email-unknown.invalid").is_ok());
+
+        assert_eq!(
+            validate_email("not-an-email").unwrap_err(),
+            GitPairError::InvalidValue("Invalid email 'not-an-email': it has no '@'".to_string())
+        );
+        assert_eq!(
+            validate_email("").unwrap_err(),
+            GitPairError::InvalidValue("Email cannot be empty".to_string())
+        );
+        assert!(validate_email("@example.com").is_err());
+        assert!(validate_email("jane@").is_err());
+        assert!(validate_email("jane@@example.com").is_err());
+        assert!(validate_email("jane@example..com").is_err());
+        assert!(validate_email(".jane@example.com").is_err());
+        assert!(validate_email("jane doe@example.com").is_err());
+        assert!(validate_email("jane@example.com\nCo-authored-by: x").is_err());
+        assert!(validate_email("<jane@example.com>").is_err());
+        assert!(validate_email("jane|x@example.com").is_err());
+    }
+
+    #[test]
+    fn test_sanitize_name_and_email() {
+        assert_eq!(sanitize_name("  Jane   Doe "), "Jane Doe");
+        assert_eq!(
+            sanitize_name("Jane\nCo-authored-by: Eve"),
+            "Jane Co-authored-by: Eve"
+        );
+        assert_eq!(
+            sanitize_name("Jane <jane@example.com>"),
+            "Jane jane@example.com"
+        );
+        assert_eq!(sanitize_name("Jane|Doe\r"), "JaneDoe");
+        assert_eq!(sanitize_email(" <jane@example.com> "), "jane@example.com");
+        assert_eq!(sanitize_email("jane@example.com"), "jane@example.com");
+    }
+
+    #[test]
+    fn test_validate_alias() {
+        assert!(validate_alias("alice").is_ok());
+        assert!(validate_alias("bob-w").is_ok());
+        assert!(validate_alias("j.smith_2").is_ok());
+
+        assert!(validate_alias("").is_err());
+        assert!(validate_alias("two words")
+            .unwrap_err()
+            .to_string()
+            .contains("Use a single word such as 'two-words'"));
+        assert!(validate_alias("a|b")
+            .unwrap_err()
+            .to_string()
+            .contains("separator"));
+        assert!(validate_alias("--global").is_err());
+        assert!(validate_alias("#alice").is_err());
+        assert!(validate_alias("@backend").is_err());
+        assert!(validate_alias(&"a".repeat(33))
+            .unwrap_err()
+            .to_string()
+            .contains("too long"));
+        assert!(validate_alias("status")
+            .unwrap_err()
+            .to_string()
+            .contains("reserved"));
+        assert!(validate_alias("Init")
+            .unwrap_err()
+            .to_string()
+            .contains("reserved"));
+    }
+
+    #[test]
+    fn test_merge_git_pair_section_new_file() {
+        let existing = "";
+        let git_pair_section = "# BEGIN git-pair\necho 'git-pair'\n# END git-pair";
+
+        let result = merge_git_pair_section(existing, git_pair_section).unwrap();
+        assert!(result.starts_with("#!/bin/sh\n"));
+        assert!(result.contains("# BEGIN git-pair"));
+        assert!(result.contains("# END git-pair"));
+    }
+
+    #[test]
+    fn test_merge_git_pair_section_existing_content() {
+        let existing = "#!/bin/sh\necho 'existing hook'";
+        let git_pair_section = "# BEGIN git-pair\necho 'git-pair'\n# END git-pair";
+
+        let result = merge_git_pair_section(existing, git_pair_section).unwrap();
+        assert!(result.contains("echo 'existing hook'"));
+        assert!(result.contains("# BEGIN git-pair"));
+        assert!(result.ends_with("# END git-pair"));
+    }
+
+    #[test]
+    fn test_merge_git_pair_section_replace_existing() {
+        let existing =
+            "#!/bin/sh\necho 'before'\n# BEGIN git-pair\necho 'old'\n# END git-pair\necho 'after'";
+        let git_pair_section = "# BEGIN git-pair\necho 'new'\n# END git-pair";
+
+        let result = merge_git_pair_section(existing, git_pair_section).unwrap();
+        assert!(result.contains("echo 'before'"));
+        assert!(result.contains("echo 'new'"));
+        assert!(result.contains("echo 'after'"));
+        assert!(!result.contains("echo 'old'"));
+    }
+
+    #[test]
+    fn test_merge_git_pair_section_normalizes_crlf() {
+        let existing =
+            "#!/bin/sh\r\necho 'before'\r\n# BEGIN git-pair\r\necho 'old'\r\n# END git-pair\r\n";
+        let git_pair_section = "# BEGIN git-pair\r\necho 'new'\r\n# END git-pair";
+
+        let result = merge_git_pair_section(existing, git_pair_section).unwrap();
+        assert!(!result.contains('\r'));
+        assert!(result.starts_with("#!/bin/sh\necho 'before'\n"));
+        assert!(result.contains("echo 'new'"));
+
+        let appended =
+            merge_git_pair_section("#!/bin/sh\r\necho 'existing'\r\n", git_pair_section).unwrap();
+        assert!(!appended.contains('\r'));
+    }
+
+    #[test]
+    fn test_remove_git_pair_section_success() {
+        let content = "#!/bin/sh\necho 'before'\n# BEGIN git-pair\necho 'git-pair'\n# END git-pair\necho 'after'";
+
+        let result = remove_git_pair_section(content).unwrap();
+        assert!(result.contains("echo 'before'"));
+        assert!(result.contains("echo 'after'"));
+        assert!(!result.contains("git-pair"));
+        assert!(!result.contains("BEGIN"));
+    }
+
+    #[test]
+    fn test_remove_git_pair_section_not_found() {
+        let content = "#!/bin/sh\necho 'no git-pair here'";
+
+        let result = remove_git_pair_section(content);
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_remove_git_pair_section_only_content() {
+        let content = "#!/bin/sh\n# BEGIN git-pair\necho 'git-pair'\n# END git-pair";
+
+        let result = remove_git_pair_section(content).unwrap();
+        assert_eq!(result.trim(), "#!/bin/sh");
+    }
+
+    #[test]
+    fn test_lint_roster() {
+        let content = "# Global git-pair roster\n\
+            alice|Alice Johnson|alice@example.com\n\
+            bob|Bob Wilson|bob@example.com\n\
+            bob2|Bob W|BOB@example.com\n\
+            alice|Alice Again|alice2@example.com\n\
+            broken line\n\
+            two words|Someone|someone@example.com\n";
+
+        let problems = lint_roster(content);
+        assert_eq!(problems.len(), 4);
+        assert!(problems[0].contains("Line 6: malformed entry"));
+        assert!(problems.iter().any(|p| p.contains("contains whitespace")));
+        assert!(problems
+            .iter()
+            .any(|p| p.contains("Alias 'alice' is defined more than once")));
+        assert!(problems
+            .iter()
+            .any(|p| p.contains("shared by aliases: bob, bob2")));
+
+        assert!(lint_roster("alice|Alice Johnson|alice@example.com\n").is_empty());
+    }
+
+    #[test]
+    fn test_dedupe_roster() {
+        let content = "# Global git-pair roster\n\
+            bob|Bob Wilson|bob@example.com\n\
+            alice|Alice Johnson|alice@example.com\n\
+            bobby|Bob W|BOB@example.com\n\
+            bw|Bob Wilson| bob@example.com\n";
+
+        let (new_content, merges) = dedupe_roster(content);
+        assert_eq!(
+            merges,
+            vec![RosterMerge {
+                email: "bob@example.com".to_string(),
+                kept_alias: "bob".to_string(),
+                removed_aliases: vec!["bobby".to_string(), "bw".to_string()],
+            }]
+        );
+        assert_eq!(
+            new_content,
+            "# Global git-pair roster\n\
+            bob|Bob Wilson|bob@example.com\n\
+            alice|Alice Johnson|alice@example.com\n"
+        );
+
+        let (_, merges) = dedupe_roster("alice|Alice Johnson|alice@example.com\n");
+        assert!(merges.is_empty());
+    }
+
+    #[test]
+    fn test_rename_roster_alias() {
+        let content = "# Global git-pair roster\n\
+            alice|Alice Johnson|alice@example.com\n\
+            alicia|Alicia Keys|alicia@example.com\n";
+
+        let renamed = rename_roster_alias(content, "alice", "aj");
+        assert!(renamed.contains("aj|Alice Johnson|alice@example.com\n"));
+        assert!(renamed.contains("alicia|Alicia Keys|alicia@example.com\n"));
+        assert!(renamed.starts_with("# Global git-pair roster\n"));
+    }
+
+    #[test]
+    fn test_remove_and_update_roster_entries() {
+        let content = "# Global git-pair roster\n\
+            alice|Alice Johnson|alice@example.com|timezone=Europe/Berlin\n\
+            alicia|Alicia Keys|alicia@example.com\n";
+
+        let removed = remove_roster_alias(content, "alice");
+        assert_eq!(
+            removed,
+            "# Global git-pair roster\nalicia|Alicia Keys|alicia@example.com\n"
+        );
+        assert_eq!(remove_roster_alias(content, "ali"), content);
+
+        let updated = update_roster_entry(content, "alice", None, Some("aj@example.com"));
+        assert!(updated.contains("alice|Alice Johnson|aj@example.com|timezone=Europe/Berlin\n"));
+        let updated = update_roster_entry(content, "alicia", Some("Alicia Cook"), None);
+        assert!(updated.contains("alicia|Alicia Cook|alicia@example.com\n"));
+        assert!(updated.starts_with("# Global git-pair roster\nalice|Alice Johnson|"));
+    }
+
+    #[test]
+    fn test_roster_metadata() {
+        let content = "# Global git-pair roster\n\
+            alice|Alice Johnson|alice@example.com|pronouns=she/her|timezone=Europe/Berlin|badge=gold\n\
+            bob|Bob Wilson|bob@example.com|from the future\n\
+            carol|Carol White|carol@example.com\n";
+
+        // Entries with fields this version doesn't know still load
+        assert_eq!(parse_roster(content).len(), 3);
+        assert!(lint_roster(content).is_empty());
+
+        let alice = parse_roster_metadata(content, "alice");
+        assert_eq!(alice.pronouns.as_deref(), Some("she/her"));
+        assert_eq!(alice.timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(alice.display_name, None);
+        assert!(parse_roster_metadata(content, "bob").is_empty());
+        assert!(parse_roster_metadata(content, "zed").is_empty());
+
+        let extra = roster_entry_extra_fields(content, "alice");
+        assert_eq!(extra, "|pronouns=she/her|timezone=Europe/Berlin|badge=gold");
+        assert_eq!(roster_entry_extra_fields(content, "carol"), "");
+        let metadata = RosterMetadata {
+            display_name: Some("Ali".to_string()),
+            timezone: Some("America/New_York".to_string()),
+            ..RosterMetadata::default()
+        };
+        assert_eq!(
+            set_roster_metadata(&extra, &metadata),
+            "|pronouns=she/her|timezone=America/New_York|badge=gold|display-name=Ali"
+        );
+    }
+
+    #[test]
+    fn test_roster_entry_tags() {
+        let extra = "|pronouns=she/her|tags=team:platform, oncall";
+        assert_eq!(roster_entry_tags(extra), vec!["team:platform", "oncall"]);
+        assert!(roster_entry_tags("|pronouns=she/her").is_empty());
+
+        let tags = vec!["oncall".to_string(), "team:web".to_string()];
+        assert_eq!(
+            set_roster_entry_tags(extra, &tags),
+            "|pronouns=she/her|tags=oncall,team:web"
+        );
+        assert_eq!(set_roster_entry_tags(extra, &[]), "|pronouns=she/her");
+        assert_eq!(set_roster_entry_tags("", &tags), "|tags=oncall,team:web");
+    }
+
+    #[test]
+    fn test_parse_roster_csv() {
+        let csv = "Name,Email\n\
+                   Alice Johnson,alice@example.com\n\
+                   \n\
+                   bob,\"Wilson, Bob \"\"Bobby\"\"\",bob@example.com\r\n\
+                   ,Carol Diaz, carol@example.com \n";
+        assert_eq!(
+            parse_roster_csv(csv).unwrap(),
+            vec![
+                (
+                    None,
+                    "Alice Johnson".to_string(),
+                    "alice@example.com".to_string()
+                ),
+                (
+                    Some("bob".to_string()),
+                    "Wilson, Bob \"Bobby\"".to_string(),
+                    "bob@example.com".to_string()
+                ),
+                (
+                    None,
+                    "Carol Diaz".to_string(),
+                    "carol@example.com".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            parse_roster_csv("Alice,alice@example.com\nBob\n")
+                .unwrap_err()
+                .to_string(),
+            "Line 2: expected name,email or alias,name,email"
+        );
+        assert!(parse_roster_csv("").unwrap().is_empty());
+
+        assert_eq!(csv_field("Alice Johnson"), "Alice Johnson");
+        assert_eq!(csv_field("Johnson, \"AJ\""), "\"Johnson, \"\"AJ\"\"\"");
+        assert_eq!(
+            split_csv_line(&csv_field("Johnson, \"AJ\"")),
+            ["Johnson, \"AJ\""]
+        );
+    }
+
+    #[test]
+    fn test_parse_roster_emails() {
+        let field =
+            "alice@corp.com, github.com=alice@users.noreply.github.com,~/oss=alice@home.dev";
+        let (default, rules) = parse_roster_emails(field);
+        assert_eq!(default, Some("alice@corp.com"));
+        assert_eq!(
+            rules,
+            vec![
+                ("github.com", "alice@users.noreply.github.com"),
+                ("~/oss", "alice@home.dev")
+            ]
+        );
+        assert_eq!(primary_email(field), "alice@corp.com");
+        assert_eq!(roster_emails(field).len(), 3);
+
+        assert_eq!(primary_email("github.com=a@b.c"), "a@b.c");
+        assert_eq!(primary_email("single@example.com"), "single@example.com");
+    }
+
+    #[test]
+    fn test_session_durations() {
+        assert!(session_durations(&[], 3600, 600).is_empty());
+        assert_eq!(session_durations(&[1000], 3600, 600), vec![600]);
+        // Out of order, and the 4 hour break starts a second session
+        let hour = 3600;
+        assert_eq!(
+            session_durations(&[hour, 0, 6 * hour, hour / 2], hour, 600),
+            vec![hour + 600, 600]
+        );
+        // Exactly the gap still counts as one session
+        assert_eq!(session_durations(&[0, hour], hour, 0), vec![hour]);
+    }
+
+    #[test]
+    fn test_render_heatmap() {
+        // 2024-03-04 is a Monday
+        let monday = 19786;
+        let activity = [
+            DayActivity {
+                day: monday - 7,
+                paired: 0,
+                solo: 2,
+            },
+            DayActivity {
+                day: monday,
+                paired: 1,
+                solo: 1,
+            },
+            DayActivity {
+                day: monday + 2,
+                paired: 3,
+                solo: 0,
+            },
+        ];
+        let chart = render_heatmap(&activity, monday + 2, 52);
+        let rows: Vec<&str> = chart.lines().collect();
+        assert_eq!(rows.len(), 7);
+        assert_eq!(rows[0], "Mon ░▒");
+        assert_eq!(rows[1], "    ··");
+        assert_eq!(rows[2], "Wed ·█");
+        // Days after today are left blank
+        assert_eq!(rows[3], "    ·");
+        assert_eq!(rows[6], "Sun ·");
+
+        // Rows with nothing to show yet stay in place
+        let rows: Vec<String> = render_heatmap(&[], monday, 52)
+            .lines()
+            .map(String::from)
+            .collect();
+        assert_eq!(rows, ["Mon ·", "", "Wed", "", "Fri", "", "Sun"]);
+
+        let chart = render_heatmap(&activity, monday + 2, 1);
+        assert_eq!(chart.lines().next(), Some("Mon ▒"));
+        assert_eq!(
+            render_heatmap(&[], monday, 52).lines().next(),
+            Some("Mon ·")
+        );
+    }
+
+    #[test]
+    fn test_pairing_streaks() {
+        let paired = |day: i64| DayActivity {
+            day,
+            paired: 1,
+            solo: 0,
+        };
+        let solo = |day: i64| DayActivity {
+            day,
+            paired: 0,
+            solo: 1,
+        };
+        assert_eq!(pairing_streaks(&[], 100), (0, 0));
+
+        let activity = [
+            paired(90),
+            paired(91),
+            paired(92),
+            solo(93),
+            paired(98),
+            paired(99),
+        ];
+        assert_eq!(pairing_streaks(&activity, 99), (2, 3));
+        // Today isn't paired yet, the streak still counts
+        assert_eq!(pairing_streaks(&activity, 100), (2, 3));
+        assert_eq!(pairing_streaks(&activity, 101), (0, 3));
+    }
+
+    #[test]
+    fn test_merge_roster() {
+        let entry = |alias: &str, name: &str, email: &str| {
+            (alias.to_string(), name.to_string(), email.to_string())
+        };
+        let content = "# Global git-pair roster\nalice|Alice Old|alice@old.example|tags=web\nzoe|Zoe Adams|zoe@example.com\n";
+        let incoming = vec![
+            entry("alice", "Alice Johnson", "alice@example.com"),
+            entry("bob", "Bob Wilson", "bob@example.com"),
+            entry("zee", "Zoe Adams", "ZOE@example.com"),
+            entry("zoe", "Zoe Adams", "zoe@example.com"),
+            entry("-x", "Nobody", "nobody@example.com"),
+        ];
+
+        let (merged, changes) = merge_roster(content, &incoming, |_, _| Ok(true)).unwrap();
+        assert_eq!(
+            merged,
+            "# Global git-pair roster\nalice|Alice Johnson|alice@example.com|tags=web\nzoe|Zoe Adams|zoe@example.com\nbob|Bob Wilson|bob@example.com\n"
+        );
+        assert_eq!(changes.added, vec![incoming[1].clone()]);
+        assert_eq!(
+            changes.updated,
+            vec![(
+                entry("alice", "Alice Old", "alice@old.example"),
+                incoming[0].clone()
+            )]
+        );
+        assert_eq!(changes.skipped.len(), 2);
+        assert_eq!(changes.skipped[0].1, "<ZOE@example.com> is already 'zoe'");
+        assert!(!changes.is_empty());
+
+        let (merged, changes) = merge_roster(content, &incoming[..1], |_, _| Ok(false)).unwrap();
+        assert_eq!(merged, content);
+        assert_eq!(changes.kept.len(), 1);
+        assert!(changes.is_empty());
+
+        assert_eq!(
+            merge_roster(content, &incoming, |_, _| Err(GitPairError::Cancelled)).unwrap_err(),
+            GitPairError::Cancelled
+        );
+    }
+
+    #[test]
+    fn test_roster_header() {
+        let content =
+            "# Global git-pair roster\n# Format: alias|name|email\nalice|Alice|alice@example.com\n";
+        assert_eq!(roster_header(content, "sync-source"), None);
+
+        let content = set_roster_header(content, "sync-source", "git@example.com:team/people.git");
+        assert_eq!(
+            content,
+            "# Global git-pair roster\n# Format: alias|name|email\n# sync-source=git@example.com:team/people.git\nalice|Alice|alice@example.com\n"
+        );
+        assert_eq!(
+            roster_header(&content, "sync-source").as_deref(),
+            Some("git@example.com:team/people.git")
+        );
+        let content = set_roster_header(&content, "sync-source", "/srv/roster");
+        assert_eq!(
+            roster_header(&content, "sync-source").as_deref(),
+            Some("/srv/roster")
+        );
+        assert_eq!(content.matches("sync-source").count(), 1);
+        assert_eq!(set_roster_header("", "k", "v"), "# k=v\n");
+    }
+
+    #[test]
+    fn test_roster_drift() {
+        let entry = |alias: &str, name: &str, email: &str| {
+            (alias.to_string(), name.to_string(), email.to_string())
+        };
+        let managed = [
+            entry("alice", "Alice Johnson", "alice@corp.com"),
+            entry("bob", "Bob Wilson", "bob@corp.com"),
+        ];
+        let local = [
+            entry("alice", "Alice Johnson", "alice@corp.com"),
+            entry("bob", "Bobby", "bob@corp.com"),
+            entry("aj", "Alice J", "Alice@Corp.com"),
+            entry("carol", "Carol Smith", "carol@corp.com"),
+        ];
+        let drift = roster_drift(&managed, &local);
+        assert_eq!(drift.len(), 2);
+        assert!(drift[0].starts_with("'bob' is Bobby <bob@corp.com>"));
+        assert!(drift[1].contains("'aj'") && drift[1].contains("'alice'"));
+        assert!(roster_drift(&managed, &managed).is_empty());
+    }
+
+    #[test]
+    fn test_unused_roster_entries() {
+        let entry =
+            |alias: &str, email: &str| (alias.to_string(), alias.to_string(), email.to_string());
+        let roster = [
+            entry("alice", "alice@corp.com"),
+            entry(
+                "bob",
+                "bob@corp.com,github.com=bob@users.noreply.github.com",
+            ),
+            entry("carol", "carol@corp.com"),
+            entry("dave", "dave@corp.com"),
+        ];
+        let day = 86400;
+        let now = 400 * day;
+        let last_used = [
+            ("alice@corp.com".to_string(), 10 * day),
+            ("alice@corp.com".to_string(), 390 * day),
+            ("bob@users.noreply.github.com".to_string(), 100 * day),
+            ("dave@corp.com".to_string(), 5 * day),
+        ];
+
+        // carol was never seen, dave is already archived
+        let unused = unused_roster_entries(&roster, &["dave".to_string()], &last_used, now, 6);
+        let aliases: Vec<&str> = unused.iter().map(|(alias, _, _)| alias.as_str()).collect();
+        assert_eq!(aliases, vec!["bob"]);
+        assert_eq!(
+            unused_roster_entries(&roster, &[], &last_used, now, 12),
+            vec![roster[3].clone()]
+        );
+    }
+
+    #[test]
+    fn test_render_footer() {
+        assert_eq!(branch_ticket("feature/PAY-123-refunds"), Some("PAY-123"));
+        assert_eq!(branch_ticket("A1-2"), Some("A1-2"));
+        assert_eq!(branch_ticket("fix/Login-42"), None);
+        assert_eq!(branch_ticket("x/PAY-refunds-OPS-7"), Some("OPS-7"));
+        assert_eq!(branch_ticket("main"), None);
+
+        assert_eq!(
+            render_footer(
+                "Refs: {ticket}\nBranch: {branch}",
+                "feature/PAY-123-refunds"
+            ),
+            "Refs: PAY-123\nBranch: feature/PAY-123-refunds"
+        );
+        assert_eq!(render_footer("Refs: {ticket}", "main"), "Refs: ");
+    }
+
+    #[test]
+    fn test_render_header_template() {
+        let variables = [("branch", "feature/login"), ("repo", "shop")];
+        assert_eq!(
+            render_header_template(
+                "Pairing on {branch} in {repo}\\nGuidelines: https://wiki.example.com/pairing",
+                &variables
+            ),
+            "# Pairing on feature/login in shop\n# Guidelines: https://wiki.example.com/pairing"
+        );
+        assert_eq!(
+            render_header_template("## {repo}\\n\\n{unknown}", &variables),
+            "## shop\n#\n# {unknown}"
+        );
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        assert_eq!(
+            diff_lines("Fix\n", "Fix\n\nCo-authored-by: A <a@x.com>\n"),
+            [
+                (' ', "Fix"),
+                ('+', ""),
+                ('+', "Co-authored-by: A <a@x.com>")
+            ]
+        );
+        assert_eq!(
+            diff_lines("a\nb\nc\n", "a\nB\nc\n"),
+            [(' ', "a"), ('-', "b"), ('+', "B"), (' ', "c")]
+        );
+        assert_eq!(diff_lines("same\n", "same\n"), [(' ', "same")]);
+        assert!(diff_lines("", "").is_empty());
+    }
+
+    #[test]
+    fn test_share_snippets() {
+        let snippet = PairSnippet {
+            coauthors: vec![
+                SharedCoauthor {
+                    name: "Jane Doe".to_string(),
+                    email: "jane@example.com".to_string(),
+                    ..SharedCoauthor::default()
+                },
+                SharedCoauthor {
+                    name: "Zoë Ångström".to_string(),
+                    email: "zoe@example.com".to_string(),
+                    co_developed: true,
+                    driver: true,
+                },
+            ],
+            settings: vec![
+                ("trailer-order".to_string(), "rotate".to_string()),
+                ("commit-sources".to_string(), "editor,message".to_string()),
+            ],
+        };
+        let encoded = encode_snippet(&snippet);
+        assert!(encoded.starts_with("git-pair:1:"));
+        assert!(!encoded.contains(['+', '/', '=', ' ']));
+        assert_eq!(decode_snippet(&encoded).unwrap(), snippet);
+
+        // Every padding length round-trips
+        for name in ["A", "AB", "ABC"] {
+            let person = PairSnippet {
+                coauthors: vec![SharedCoauthor {
+                    name: name.to_string(),
+                    email: "a@b".to_string(),
+                    ..SharedCoauthor::default()
+                }],
+                settings: Vec::new(),
+            };
+            assert_eq!(decode_snippet(&encode_snippet(&person)).unwrap(), person);
+        }
+
+        // Found inside a URL or message
+        let wrapped = format!("Pair with us: https://example.com/pair?s={}&x=1", encoded);
+        assert_eq!(decode_snippet(&wrapped).unwrap(), snippet);
+
+        assert!(decode_snippet("hello")
+            .unwrap_err()
+            .to_string()
+            .contains("expected 'git-pair:...'"));
+        assert!(decode_snippet("git-pair:2:abc")
+            .unwrap_err()
+            .to_string()
+            .contains("needs a newer git-pair"));
+        assert!(decode_snippet("git-pair:1:a*b")
+            .unwrap_err()
+            .to_string()
+            .contains("unexpected character '*'"));
+        assert!(decode_snippet("git-pair:1:")
+            .unwrap_err()
+            .to_string()
+            .contains("no co-authors"));
+        // "Jane" with no email
+        assert!(decode_snippet("git-pair:1:SmFuZQ")
+            .unwrap_err()
+            .to_string()
+            .contains("bad co-author 'Jane'"));
+    }
+
+    #[test]
+    fn test_insert_trailer_block() {
+        let block = "Co-authored-by: Jane Doe <jane@example.com>\n";
+        let insert = |message: &str| insert_trailer_block(message, block, '#');
+
+        // Exactly one blank line, however the message ends
+        for message in ["a\n\nb", "a\n\nb\n", "a\n\nb\n\n\n"] {
+            assert_eq!(
+                insert(message),
+                "a\n\nb\n\nCo-authored-by: Jane Doe <jane@example.com>\n"
+            );
+        }
+        assert_eq!(
+            insert(""),
+            "\n\nCo-authored-by: Jane Doe <jane@example.com>\n"
+        );
+
+        // An existing trailer paragraph is extended, but a subject is never taken for one
+        assert_eq!(
+            insert("Fix login\n\nRefs: PAY-1\nSigned-off-by: Me <me@example.com>\n"),
+            "Fix login\n\nRefs: PAY-1\nSigned-off-by: Me <me@example.com>\n\
+             Co-authored-by: Jane Doe <jane@example.com>\n"
+        );
+        assert_eq!(
+            insert("fix: login\n"),
+            "fix: login\n\nCo-authored-by: Jane Doe <jane@example.com>\n"
+        );
+        assert_eq!(
+            insert("Fix\n\nSee the notes: they explain it\n"),
+            "Fix\n\nSee the notes: they explain it\n\n\
+             Co-authored-by: Jane Doe <jane@example.com>\n"
+        );
+
+        // Editor comments and the scissors section stay last
+        assert_eq!(
+            insert("Fix\n\n# Please enter the commit message\n#\n"),
+            "Fix\n\nCo-authored-by: Jane Doe <jane@example.com>\n\n\
+             # Please enter the commit message\n#\n"
+        );
+        let scissors = format!("#{}", SCISSORS_LINE);
+        assert_eq!(
+            insert(&format!("Fix\n{}\n# Do not modify\ndiff --git a/x b/x\n", scissors)),
+            format!(
+                "Fix\n\nCo-authored-by: Jane Doe <jane@example.com>\n\n{}\n# Do not modify\ndiff --git a/x b/x\n",
+                scissors
+            )
+        );
+        assert_eq!(
+            insert_trailer_block("Fix\n; comment\n", block, ';'),
+            "Fix\n\nCo-authored-by: Jane Doe <jane@example.com>\n\n; comment\n"
+        );
+    }
+
+    #[test]
+    fn test_message_encoding() {
+        assert_eq!(
+            MessageEncoding::parse("ISO-8859-1"),
+            Some(MessageEncoding::Latin1)
+        );
+        assert_eq!(MessageEncoding::parse("utf8"), Some(MessageEncoding::Utf8));
+        assert_eq!(
+            MessageEncoding::parse("CP1252"),
+            Some(MessageEncoding::Windows1252)
+        );
+        assert_eq!(
+            MessageEncoding::parse("latin_9"),
+            Some(MessageEncoding::Latin9)
+        );
+        assert_eq!(MessageEncoding::parse("Shift_JIS"), None);
+
+        // Every byte survives a round trip through the single-byte encodings
+        let bytes: Vec<u8> = (0..=255).collect();
+        for encoding in [
+            MessageEncoding::Latin1,
+            MessageEncoding::Latin9,
+            MessageEncoding::Windows1252,
+        ] {
+            let text = encoding.decode(&bytes).unwrap();
+            assert_eq!(
+                encoding.encode(&text),
+                (bytes.clone(), false),
+                "{:?}",
+                encoding
+            );
+        }
+
+        assert_eq!(
+            MessageEncoding::Latin1.encode("José Núñez"),
+            (b"Jos\xe9 N\xfa\xf1ez".to_vec(), false)
+        );
+        assert_eq!(
+            MessageEncoding::Windows1252.encode("Œuvre – €5"),
+            (b"\x8cuvre \x96 \x805".to_vec(), false)
+        );
+        assert_eq!(
+            MessageEncoding::Latin9.encode("Šárka €"),
+            (b"\xa6\xe1rka \xa4".to_vec(), false)
+        );
+        // Latin-9 gave the currency sign's byte to the euro
+        assert_eq!(MessageEncoding::Latin9.encode("¤"), (b"?".to_vec(), true));
+        assert_eq!(
+            MessageEncoding::Latin1.encode("Łukasz"),
+            (b"?ukasz".to_vec(), true)
+        );
+        assert_eq!(MessageEncoding::Latin9.decode(b"\xa4\xbd").unwrap(), "€œ");
+        assert!(MessageEncoding::Utf8.decode(b"\xe9").is_err());
+    }
+
+    #[test]
+    fn test_command_spec_check_arguments() {
+        let spec = CommandSpec {
+            name: "clean-hooks",
+            subcommands: &[],
+            options: &["--dry-run", "--mode"],
+            arguments: false,
+            aliases: false,
+        };
+        let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            spec.check_arguments(&args(&["--dry-run", "--mode=x"])),
+            None
+        );
+        assert_eq!(
+            spec.check_arguments(&args(&["--dry-rum"])).unwrap(),
+            "Unknown option '--dry-rum' for 'git-pair clean-hooks'"
+        );
+        assert_eq!(
+            spec.check_arguments(&args(&["now"])).unwrap(),
+            "'git-pair clean-hooks' takes no arguments, got 'now'"
+        );
+
+        let spec = CommandSpec {
+            name: "cache",
+            subcommands: &["clear"],
+            ..spec
+        };
+        assert_eq!(spec.check_arguments(&args(&["clear"])), None);
+    }
+
+    #[test]
+    fn test_completion_script() {
+        let commands = [
+            CommandSpec {
+                name: "add",
+                subcommands: &[],
+                options: &["--global", "--once"],
+                arguments: true,
+                aliases: true,
+            },
+            CommandSpec {
+                name: "roster",
+                subcommands: &["lint", "sync"],
+                options: &["--dry-run"],
+                arguments: false,
+                aliases: false,
+            },
+            CommandSpec {
+                name: "pause",
+                subcommands: &[],
+                options: &[],
+                arguments: false,
+                aliases: false,
+            },
+        ];
+
+        let bash = completion_script("bash", &commands).unwrap();
+        assert!(bash.contains("        words=\"add roster pause\"\n"));
+        assert!(bash.contains("            add) words=\"--global --once\" ;;\n"));
+        assert!(bash.contains("            roster) words=\"lint sync --dry-run\" ;;\n"));
+        assert!(!bash.contains("pause)"));
+        assert!(bash.contains("            add)\n                [[ $cur == -* ]] ||"));
+        assert!(bash.contains("complete -F _git_pair_complete git-pair\n"));
+
+        let zsh = completion_script("zsh", &commands).unwrap();
+        assert!(zsh.starts_with("#compdef git-pair\n"));
+        assert!(zsh.contains("        roster) compadd -- lint sync --dry-run ;;\n"));
+
+        let fish = completion_script("fish", &commands).unwrap();
+        assert!(fish.contains(
+            "complete -c git-pair -n '__fish_seen_subcommand_from roster' -a 'lint sync'\n"
+        ));
+        assert!(
+            fish.contains("complete -c git-pair -n '__fish_seen_subcommand_from add' -l once\n")
+        );
+        assert!(fish.contains("-n '__fish_seen_subcommand_from add' -a '(git-pair completions --aliases 2>/dev/null)'"));
+
+        let powershell = completion_script("powershell", &commands).unwrap();
+        assert!(powershell.contains("        'roster' = @('lint', 'sync', '--dry-run')\n"));
+        assert!(powershell.contains("        'pause' = @()\n"));
+        assert!(powershell.contains("    $aliasCommands = @('add')\n"));
+
+        assert_eq!(
+            completion_script("tcsh", &commands)
+                .unwrap_err()
+                .to_string(),
+            "Unknown shell 'tcsh', expected one of: bash, zsh, fish, powershell"
+        );
+    }
+}
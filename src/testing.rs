@@ -0,0 +1,102 @@
+//! Helpers for throwaway git repositories with git-pair state, for tests of git-pair
+//! itself and of tools built on it. Available with the `test-util` feature.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Keeps names unique when several are created within the same clock tick
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn unique_temp_path() -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+
+    let mut temp_path = env::temp_dir();
+    temp_path.push(format!(
+        "git-pair-test-{}-{}-{}",
+        std::process::id(),
+        timestamp,
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    temp_path
+}
+
+/// A temporary directory that is removed when dropped
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new() -> io::Result<Self> {
+        let temp_path = unique_temp_path();
+        fs::create_dir_all(&temp_path)?;
+        Ok(TempDir { path: temp_path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Creates an empty temporary file, e.g. for a roster pointed to by
+/// `GIT_PAIR_ROSTER_FILE`. The caller removes it.
+pub fn create_temp_file() -> io::Result<PathBuf> {
+    let temp_path = unique_temp_path();
+    fs::write(&temp_path, "")?;
+    Ok(temp_path)
+}
+
+/// Creates a temporary git repository with a committer identity configured, without
+/// changing the process's working directory
+pub fn setup_test_repo() -> io::Result<TempDir> {
+    let temp_dir = TempDir::new()?;
+    let repo_path = temp_dir.path();
+
+    Command::new("git")
+        .args(["init"])
+        .current_dir(repo_path)
+        .output()?;
+
+    // Configure git user (required for commits)
+    Command::new("git")
+        .args(["config", "user.name", "Test User"])
+        .current_dir(repo_path)
+        .output()?;
+
+    Command::new("git")
+        .args(["config", "user.email", "test@example.com"])
+        .current_dir(repo_path)
+        .output()?;
+
+    Ok(temp_dir)
+}
+
+/// Creates a temporary git repository with git-pair initialized on the current branch
+/// and the given `(name, surname, email)` co-authors added, hook included
+pub fn setup_paired_repo(coauthors: &[(&str, &str, &str)]) -> Result<TempDir, crate::GitPairError> {
+    let temp_dir =
+        setup_test_repo().map_err(|e| crate::GitPairError::io("creating test repository", e))?;
+    crate::init_pair_config_in(temp_dir.path())?;
+    for (name, surname, email) in coauthors {
+        crate::add_coauthor_in(
+            temp_dir.path(),
+            name,
+            surname,
+            email,
+            &crate::AddOptions::default(),
+        )?;
+    }
+    Ok(temp_dir)
+}